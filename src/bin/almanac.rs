@@ -0,0 +1,68 @@
+extern crate aoc_2023;
+
+use std::{env, fs, ops::Range, process};
+
+use aoc_2023::days::day5::Almanac;
+
+const USAGE: &str =
+    "usage: almanac [--input <path>] (--trace <seed> | --trace-range <start> <len>)...";
+const DEFAULT_INPUT: &str = "input/2023/day5.txt";
+
+enum Trace {
+    Seed(i64),
+    SeedRange(Range<i64>),
+}
+
+fn parse_number(arg: Option<String>) -> i64 {
+    arg.and_then(|arg| arg.parse().ok())
+        .unwrap_or_else(|| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{USAGE}");
+    process::exit(2)
+}
+
+fn main() {
+    let mut input_path = DEFAULT_INPUT.to_owned();
+    let mut traces = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_path = args.next().unwrap_or_else(|| exit_with_usage()),
+            "--trace" => traces.push(Trace::Seed(parse_number(args.next()))),
+            "--trace-range" => {
+                let start = parse_number(args.next());
+                let len = parse_number(args.next());
+                let end = start.checked_add(len).unwrap_or_else(|| exit_with_usage());
+                traces.push(Trace::SeedRange(start..end));
+            }
+            _ => exit_with_usage(),
+        }
+    }
+
+    if traces.is_empty() {
+        exit_with_usage();
+    }
+
+    let input = fs::read_to_string(&input_path).unwrap_or_else(|err| {
+        eprintln!("failed to read {input_path}: {err}");
+        process::exit(1)
+    });
+    let almanac = Almanac::parse_str(&input).unwrap_or_else(|err| {
+        eprintln!("failed to parse {input_path}: {err}");
+        process::exit(1)
+    });
+
+    for (i, trace) in traces.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        match trace {
+            Trace::Seed(seed) => println!("{}", almanac.trace(seed)),
+            Trace::SeedRange(seed_range) => println!("{}", almanac.trace_range(seed_range)),
+        }
+    }
+}
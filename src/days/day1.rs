@@ -1,47 +1,53 @@
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 1;
+
 #[aoc_runner_derive::aoc(day1, part1)]
-pub fn solve_part1(input: &str) -> u32 {
-    input
-        .lines()
+pub fn solve_part1(input: &str) -> Result<u32, ParseError> {
+    input_lines(DAY, input)
         .map(|line| {
             const ZERO: u8 = b'0';
             let mut digits = line
+                .as_str()
                 .bytes()
                 .filter(|ch| ch.is_ascii_digit())
                 .map(|ch| ch - ZERO);
 
-            let digit1 = digits.next().unwrap();
-            let digit2 = digits.last().unwrap_or(digit1);
+            let digit1 = digits
+                .next()
+                .ok_or_else(|| line.error(line.as_str(), "a digit"))?;
+            let digit2 = digits.next_back().unwrap_or(digit1);
             let digits = digit1 * 10u8 + digit2;
 
-            digits as u32
+            Ok(digits as u32)
         })
         .sum()
 }
 
 #[aoc_runner_derive::aoc(day1, part2)]
-pub fn solve_part2(input: &str) -> u32 {
-    input
-        .lines()
+pub fn solve_part2(input: &str) -> Result<u32, ParseError> {
+    input_lines(DAY, input)
         .map(|line| {
             let mut digit1 = Option::<u8>::None;
             let mut digit2 = Option::<u8>::None;
 
-            for i in usize::MIN..line.len() {
-                let slice = &line[i..];
+            let bytes = line.as_str().as_bytes();
+            for i in usize::MIN..bytes.len() {
+                let slice = &bytes[i..];
                 let digit = match slice {
-                    one if one.starts_with("one") => Some(1u8),
-                    two if two.starts_with("two") => Some(2u8),
-                    three if three.starts_with("three") => Some(3u8),
-                    four if four.starts_with("four") => Some(4u8),
-                    five if five.starts_with("five") => Some(5u8),
-                    six if six.starts_with("six") => Some(6u8),
-                    seven if seven.starts_with("seven") => Some(7u8),
-                    eight if eight.starts_with("eight") => Some(8u8),
-                    nine if nine.starts_with("nine") => Some(9u8),
+                    one if one.starts_with(b"one") => Some(1u8),
+                    two if two.starts_with(b"two") => Some(2u8),
+                    three if three.starts_with(b"three") => Some(3u8),
+                    four if four.starts_with(b"four") => Some(4u8),
+                    five if five.starts_with(b"five") => Some(5u8),
+                    six if six.starts_with(b"six") => Some(6u8),
+                    seven if seven.starts_with(b"seven") => Some(7u8),
+                    eight if eight.starts_with(b"eight") => Some(8u8),
+                    nine if nine.starts_with(b"nine") => Some(9u8),
                     _ => {
-                        let digit = slice.bytes().next().unwrap();
+                        let digit = slice[0];
                         match digit {
-                            b'0'..=b'9' => Some(digit),
+                            b'0'..=b'9' => Some(digit - b'0'),
                             _ => None,
                         }
                     }
@@ -56,11 +62,12 @@ pub fn solve_part2(input: &str) -> u32 {
                 }
             }
 
-            let digit1 = digit1.unwrap();
-            let digit2 = digit2.unwrap();
+            let expected = "a digit or a spelled-out digit";
+            let digit1 = digit1.ok_or_else(|| line.error(line.as_str(), expected))?;
+            let digit2 = digit2.unwrap_or(digit1);
             let digits = digit1 * 10u8 + digit2;
 
-            digits as u32
+            Ok(digits as u32)
         })
         .sum()
 }
@@ -71,7 +78,7 @@ mod tests {
     fn test_solve_part1_sample() {
         assert_eq!(
             super::solve_part1("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet"),
-            142
+            Ok(142)
         )
     }
 
@@ -79,7 +86,22 @@ mod tests {
     fn test_solve_part2_sample() {
         assert_eq!(
             super::solve_part2("two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen"),
-            281
+            Ok(281)
+        )
+    }
+
+    #[test]
+    fn test_solve_line_without_digits() {
+        let error = super::solve_part1("1abc2\nabc").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 2, column 1: expected a digit"
+        );
+
+        let error = super::solve_part2("one\nabc").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 2, column 1: expected a digit or a spelled-out digit"
         )
     }
 }
@@ -0,0 +1,384 @@
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 10;
+
+pub type Coords = (usize, usize);
+
+const NORTH: u8 = 1 << 0;
+const EAST: u8 = 1 << 1;
+const SOUTH: u8 = 1 << 2;
+const WEST: u8 = 1 << 3;
+
+const START: u8 = b'S';
+
+/// Returns the directions `tile` connects to, as a mask of [`NORTH`], [`EAST`], [`SOUTH`] and
+/// [`WEST`].
+const fn get_connections(tile: u8) -> Option<u8> {
+    let connections = match tile {
+        b'|' => NORTH | SOUTH,
+        b'-' => EAST | WEST,
+        b'L' => NORTH | EAST,
+        b'J' => NORTH | WEST,
+        b'7' => SOUTH | WEST,
+        b'F' => SOUTH | EAST,
+        b'.' => 0,
+        _ => return None,
+    };
+
+    Some(connections)
+}
+
+const fn get_tile(connections: u8) -> u8 {
+    match connections {
+        0b0101 => b'|',
+        0b1010 => b'-',
+        0b0011 => b'L',
+        0b1001 => b'J',
+        0b1100 => b'7',
+        0b0110 => b'F',
+        _ => b'.',
+    }
+}
+
+#[inline]
+const fn get_opposite(direction: u8) -> u8 {
+    ((direction << 2) | (direction >> 2)) & 0b1111
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PipeMaze {
+    connections: Vec<u8>,
+    width: usize,
+    height: usize,
+    start: Coords,
+    main_loop: Vec<Coords>,
+}
+
+impl PipeMaze {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut connections = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+        let mut start = None;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            for (col, &tile) in row.iter().enumerate() {
+                if tile == START {
+                    if start.is_some() {
+                        return Err(line.error(&line.as_str()[col..], "a single start tile"));
+                    }
+
+                    start = Some((height, col));
+                    connections.push(0);
+                    continue;
+                }
+
+                let tile_connections = get_connections(tile)
+                    .ok_or_else(|| line.error(&line.as_str()[col..], "a pipe, `.` or `S`"))?;
+                connections.push(tile_connections);
+            }
+
+            height += 1;
+        }
+
+        let start = start.ok_or(ParseError::new(DAY, height.max(1), 1, "a start tile"))?;
+        let mut maze = Self {
+            connections,
+            width,
+            height,
+            start,
+            main_loop: Vec::new(),
+        };
+
+        // Any two of the pipes pointing at the start tile may be the ones closing the loop.
+        let (row, col) = start;
+        let candidates = maze.infer_start_connections();
+        for first in [NORTH, EAST, SOUTH, WEST] {
+            for second in [NORTH, EAST, SOUTH, WEST]
+                .into_iter()
+                .filter(|&second| second > first)
+            {
+                if candidates & first == 0 || candidates & second == 0 {
+                    continue;
+                }
+
+                maze.connections[row * width + col] = first | second;
+                if let Some(main_loop) = maze.trace_loop() {
+                    maze.main_loop = main_loop;
+                    return Ok(maze);
+                }
+            }
+        }
+
+        let expected = "a start tile on a closed loop";
+        Err(ParseError::new(DAY, row + 1, col + 1, expected))
+    }
+
+    /// Returns every direction from the start tile with a pipe pointing back at it.
+    fn infer_start_connections(&self) -> u8 {
+        [NORTH, EAST, SOUTH, WEST]
+            .into_iter()
+            .filter(|&direction| {
+                self.get_neighbour(self.start, direction)
+                    .is_some_and(|neighbour| {
+                        self.get_connections(neighbour) & get_opposite(direction) != 0
+                    })
+            })
+            .fold(0, |connections, direction| connections | direction)
+    }
+
+    #[inline]
+    fn get_connections(&self, (row, col): Coords) -> u8 {
+        self.connections[row * self.width + col]
+    }
+
+    fn get_neighbour(&self, (row, col): Coords, direction: u8) -> Option<Coords> {
+        match direction {
+            NORTH if row > 0 => Some((row - 1, col)),
+            EAST if col + 1 < self.width => Some((row, col + 1)),
+            SOUTH if row + 1 < self.height => Some((row + 1, col)),
+            WEST if col > 0 => Some((row, col - 1)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub const fn start(&self) -> Coords {
+        self.start
+    }
+
+    /// Returns the pipe hidden under the start tile.
+    #[inline]
+    pub fn get_start_tile(&self) -> u8 {
+        get_tile(self.get_connections(self.start))
+    }
+
+    /// Returns the tiles of the loop through the start tile, in order, starting with it.
+    #[inline]
+    pub fn get_loop(&self) -> &[Coords] {
+        &self.main_loop
+    }
+
+    fn trace_loop(&self) -> Option<Vec<Coords>> {
+        let mut main_loop = vec![self.start];
+
+        let mut curr_tile = self.start;
+        let mut direction = 1 << self.get_connections(self.start).trailing_zeros();
+        loop {
+            curr_tile = self.get_neighbour(curr_tile, direction)?;
+            if curr_tile == self.start {
+                let is_closed = self.get_connections(self.start) & get_opposite(direction) != 0;
+                break is_closed.then_some(main_loop);
+            }
+
+            main_loop.push(curr_tile);
+            direction = self.get_connections(curr_tile) & !get_opposite(direction);
+        }
+    }
+
+    /// Counts the tiles enclosed by the loop by scanning every row and flipping between outside
+    /// and inside at each loop tile that connects north.
+    pub fn count_enclosed_tiles(&self) -> usize {
+        let mut is_loop = vec![false; self.connections.len()];
+        for &(row, col) in &self.main_loop {
+            is_loop[row * self.width + col] = true;
+        }
+
+        let mut enclosed_tiles = usize::MIN;
+        for row in 0..self.height {
+            let mut is_inside = false;
+            for col in 0..self.width {
+                if is_loop[row * self.width + col] {
+                    if self.get_connections((row, col)) & NORTH != 0 {
+                        is_inside = !is_inside;
+                    }
+                } else if is_inside {
+                    enclosed_tiles += 1;
+                }
+            }
+        }
+
+        enclosed_tiles
+    }
+
+    /// Counts the tiles enclosed by the loop from its area (shoelace formula) and Pick's theorem.
+    pub fn count_enclosed_tiles_by_area(&self) -> usize {
+        let main_loop = &self.main_loop;
+        let double_area = main_loop
+            .iter()
+            .zip(main_loop.iter().cycle().skip(1))
+            .map(|(&(row1, col1), &(row2, col2))| (col1 * row2) as i64 - (col2 * row1) as i64)
+            .sum::<i64>()
+            .unsigned_abs() as usize;
+
+        // Pick's theorem: area = interior + boundary / 2 - 1
+        (double_area + 2 - main_loop.len()) / 2
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day10)]
+pub fn generate_input(input: &str) -> Result<PipeMaze, ParseError> {
+    PipeMaze::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day10, part1)]
+pub fn solve_part1(input: &PipeMaze) -> usize {
+    input.get_loop().len() / 2
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day10, part2)]
+pub fn solve_part2(input: &PipeMaze) -> usize {
+    input.count_enclosed_tiles()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_solve_part1_sample_1() {
+        let input = super::generate_input(
+            "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part1(&input), 4)
+    }
+
+    #[test]
+    fn test_solve_part1_sample_2() {
+        let input = super::generate_input(
+            "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ",
+        )
+        .unwrap();
+        assert_eq!(input.get_start_tile(), b'F');
+        assert_eq!(super::solve_part1(&input), 8)
+    }
+
+    #[test]
+    fn test_solve_part2_sample_1() {
+        let input = super::generate_input(
+            "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), 4);
+        assert_eq!(input.count_enclosed_tiles_by_area(), 4)
+    }
+
+    #[test]
+    fn test_solve_part2_sample_2() {
+        let input = super::generate_input(
+            ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), 8);
+        assert_eq!(input.count_enclosed_tiles_by_area(), 8)
+    }
+
+    #[test]
+    fn test_solve_part2_sample_3() {
+        let input = super::generate_input(
+            "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), 10);
+        assert_eq!(input.count_enclosed_tiles_by_area(), 10)
+    }
+
+    #[test]
+    fn test_get_loop_sample() {
+        let input = super::generate_input(
+            ".....
+.S-7.
+.|.|.
+.L-J.
+.....",
+        )
+        .unwrap();
+        assert_eq!(
+            input.get_loop(),
+            [
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (3, 2),
+                (3, 1),
+                (2, 1)
+            ]
+        )
+    }
+
+    #[test]
+    fn test_get_loop_extra_start_neighbour() {
+        // The pipe north of the start tile points at it but is not part of the loop.
+        let input = super::generate_input(
+            ".|...
+.S-7.
+.|.|.
+.L-J.",
+        )
+        .unwrap();
+        assert_eq!(input.get_start_tile(), b'F');
+        assert_eq!(input.get_loop().len(), 8);
+        assert_eq!(super::solve_part1(&input), 4)
+    }
+
+    #[test]
+    fn test_generate_input_broken_loop() {
+        let error = super::generate_input(
+            ".....
+.S-7.
+.|.|.
+.L-..
+.....",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 10, line 2, column 2: expected a start tile on a closed loop"
+        )
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 11;
+
+pub type Coords = (usize, usize);
+
+const GALAXY: u8 = b'#';
+const EMPTY_SPACE: u8 = b'.';
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GalaxyImage {
+    galaxies: Vec<Coords>,
+    width: usize,
+    height: usize,
+}
+
+impl GalaxyImage {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut galaxies = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            for (col, &pixel) in row.iter().enumerate() {
+                match pixel {
+                    GALAXY => galaxies.push((height, col)),
+                    EMPTY_SPACE => {}
+                    _ => return Err(line.error(&line.as_str()[col..], "`#` or `.`")),
+                }
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            galaxies,
+            width,
+            height,
+        })
+    }
+
+    /// Returns the galaxies in row-major order, before expansion.
+    #[inline]
+    pub fn galaxies(&self) -> &[Coords] {
+        &self.galaxies
+    }
+
+    /// Sums the distances between every pair of galaxies once each empty row and column has
+    /// been replaced by `expansion_factor` of them.
+    pub fn get_sum_of_distances(&self, expansion_factor: u64) -> u64 {
+        let rows = self.galaxies.iter().map(|&(row, _)| row);
+        let cols = self.galaxies.iter().map(|&(_, col)| col);
+
+        get_sum_of_axis_distances(rows, self.height, expansion_factor)
+            + get_sum_of_axis_distances(cols, self.width, expansion_factor)
+    }
+}
+
+/// Sums the pairwise distances between `positions` along one axis of length `len`, after every
+/// position no galaxy occupies has been expanded `expansion_factor` times.
+fn get_sum_of_axis_distances(
+    positions: impl Iterator<Item = usize> + Clone,
+    len: usize,
+    expansion_factor: u64,
+) -> u64 {
+    let mut is_occupied = vec![false; len];
+    for position in positions.clone() {
+        is_occupied[position] = true;
+    }
+
+    let mut expanded_positions = Vec::with_capacity(len);
+    let mut expanded_position = u64::MIN;
+    for is_occupied in is_occupied {
+        expanded_positions.push(expanded_position);
+        expanded_position += if is_occupied { 1 } else { expansion_factor };
+    }
+
+    let mut positions = positions
+        .map(|position| expanded_positions[position])
+        .collect::<Vec<_>>();
+    positions.sort_unstable();
+
+    let mut sum_of_distances = u64::MIN;
+    let mut sum_of_prev_positions = u64::MIN;
+    for (i, position) in (0..).zip(positions) {
+        sum_of_distances += i * position - sum_of_prev_positions;
+        sum_of_prev_positions += position;
+    }
+
+    sum_of_distances
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day11)]
+pub fn generate_input(input: &str) -> Result<GalaxyImage, ParseError> {
+    GalaxyImage::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day11, part1)]
+pub fn solve_part1(input: &GalaxyImage) -> u64 {
+    input.get_sum_of_distances(2)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day11, part2)]
+pub fn solve_part2(input: &GalaxyImage) -> u64 {
+    input.get_sum_of_distances(1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 374)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 82000210)
+    }
+
+    #[test]
+    fn test_get_sum_of_distances_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(input.get_sum_of_distances(1), 292);
+        assert_eq!(input.get_sum_of_distances(10), 1030);
+        assert_eq!(input.get_sum_of_distances(100), 8410)
+    }
+
+    #[test]
+    fn test_get_sum_of_distances_by_pairs() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let galaxies = input.galaxies();
+        let expected = (2..=5)
+            .map(|expansion_factor| {
+                let expanded = |position: usize, is_galaxy: &dyn Fn(usize) -> bool| {
+                    (0..position)
+                        .map(|i| if is_galaxy(i) { 1 } else { expansion_factor })
+                        .sum::<u64>()
+                };
+                let has_row = |row| galaxies.iter().any(|&(r, _)| r == row);
+                let has_col = |col| galaxies.iter().any(|&(_, c)| c == col);
+
+                let mut sum = 0;
+                for (i, &(row1, col1)) in galaxies.iter().enumerate() {
+                    for &(row2, col2) in &galaxies[i + 1..] {
+                        sum += expanded(row1, &has_row).abs_diff(expanded(row2, &has_row));
+                        sum += expanded(col1, &has_col).abs_diff(expanded(col2, &has_col));
+                    }
+                }
+                sum
+            })
+            .collect::<Vec<_>>();
+
+        let actual = (2..=5)
+            .map(|expansion_factor| input.get_sum_of_distances(expansion_factor))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected)
+    }
+}
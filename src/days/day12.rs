@@ -0,0 +1,258 @@
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 12;
+
+const OPERATIONAL: u8 = b'.';
+const DAMAGED: u8 = b'#';
+const UNKNOWN: u8 = b'?';
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConditionRecord {
+    springs: Vec<u8>,
+    groups: Vec<usize>,
+}
+
+impl ConditionRecord {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            springs: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub const fn with(springs: Vec<u8>, groups: Vec<usize>) -> Self {
+        Self { springs, groups }
+    }
+
+    pub fn parse(line: &InputLine) -> Result<Self, ParseError> {
+        let (springs, groups) = line.split_once(line.as_str(), ' ', "a space")?;
+
+        if let Some(i) = springs
+            .bytes()
+            .position(|spring| ![OPERATIONAL, DAMAGED, UNKNOWN].contains(&spring))
+        {
+            return Err(line.error(&springs[i..], "`.`, `#` or `?`"));
+        }
+
+        let groups = groups
+            .split(',')
+            .map(|group| match line.parse(group, "a positive group size") {
+                Ok(0) => Err(line.error(group, "a positive group size")),
+                result => result,
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::with(springs.as_bytes().to_vec(), groups))
+    }
+
+    #[inline]
+    pub fn springs(&self) -> &[u8] {
+        &self.springs
+    }
+
+    #[inline]
+    pub fn groups(&self) -> &[usize] {
+        &self.groups
+    }
+
+    /// Returns `times` copies of this record, with the springs joined by `?`.
+    pub fn unfold(&self, times: usize) -> Self {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * times);
+        for i in 0..times {
+            if i > 0 {
+                springs.push(UNKNOWN);
+            }
+            springs.extend_from_slice(&self.springs);
+        }
+
+        Self::with(springs, self.groups.repeat(times))
+    }
+
+    /// Returns how many consecutive springs, starting at each index, could all be damaged.
+    fn get_damageable_runs(&self) -> Vec<usize> {
+        let mut runs = vec![0; self.springs.len() + 1];
+        for (i, &spring) in self.springs.iter().enumerate().rev() {
+            if spring != OPERATIONAL {
+                runs[i] = runs[i + 1] + 1;
+            }
+        }
+
+        runs
+    }
+
+    /// Returns the index right after a group of `group` damaged springs starting at `start`, or
+    /// `None` if the group does not fit there.
+    fn get_group_end(
+        &self,
+        damageable_runs: &[usize],
+        start: usize,
+        group: usize,
+    ) -> Option<usize> {
+        let end = start + group;
+        if damageable_runs[start] < group {
+            return None;
+        }
+
+        match self.springs.get(end) {
+            None => Some(end),
+            Some(&DAMAGED) => None,
+            Some(_) => Some(end + 1),
+        }
+    }
+
+    /// Counts the arrangements of damaged springs that match the groups, tabulating the number
+    /// of ways to arrange every suffix of the springs with every suffix of the groups.
+    pub fn count_arrangements(&self) -> u64 {
+        let springs_len = self.springs.len();
+        let groups_len = self.groups.len();
+        let damageable_runs = self.get_damageable_runs();
+
+        let mut ways = vec![vec![u64::MIN; groups_len + 1]; springs_len + 1];
+        ways[springs_len][groups_len] = 1;
+
+        for i in (0..springs_len).rev() {
+            for j in 0..=groups_len {
+                let mut count = u64::MIN;
+                if self.springs[i] != DAMAGED {
+                    count += ways[i + 1][j];
+                }
+
+                if self.springs[i] != OPERATIONAL && j < groups_len {
+                    if let Some(end) = self.get_group_end(&damageable_runs, i, self.groups[j]) {
+                        count += ways[end][j + 1];
+                    }
+                }
+
+                ways[i][j] = count;
+            }
+        }
+
+        ways[0][0]
+    }
+
+    /// Lists every arrangement of damaged springs that matches the groups. This takes time
+    /// proportional to their number, so it is only meant for small records.
+    pub fn get_arrangements(&self) -> Vec<String> {
+        let mut arrangements = Vec::new();
+        let mut arrangement = Vec::with_capacity(self.springs.len());
+        let damageable_runs = self.get_damageable_runs();
+        self.push_arrangements(&damageable_runs, 0, 0, &mut arrangement, &mut arrangements);
+
+        arrangements
+    }
+
+    fn push_arrangements(
+        &self,
+        damageable_runs: &[usize],
+        i: usize,
+        j: usize,
+        arrangement: &mut Vec<u8>,
+        arrangements: &mut Vec<String>,
+    ) {
+        if i >= self.springs.len() {
+            if j == self.groups.len() {
+                arrangements.push(String::from_utf8(arrangement.clone()).unwrap());
+            }
+            return;
+        }
+
+        let len = arrangement.len();
+        if self.springs[i] != OPERATIONAL && j < self.groups.len() {
+            let group = self.groups[j];
+            if let Some(end) = self.get_group_end(damageable_runs, i, group) {
+                arrangement.resize(len + group, DAMAGED);
+                if end > i + group {
+                    arrangement.push(OPERATIONAL);
+                }
+                self.push_arrangements(damageable_runs, end, j + 1, arrangement, arrangements);
+                arrangement.truncate(len);
+            }
+        }
+
+        if self.springs[i] != DAMAGED {
+            arrangement.push(OPERATIONAL);
+            self.push_arrangements(damageable_runs, i + 1, j, arrangement, arrangements);
+            arrangement.truncate(len);
+        }
+    }
+}
+
+#[aoc_runner_derive::aoc_generator(day12)]
+pub fn generate_input(input: &str) -> Result<Vec<ConditionRecord>, ParseError> {
+    input_lines(DAY, input)
+        .map(|line| ConditionRecord::parse(&line))
+        .collect()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day12, part1)]
+pub fn solve_part1(input: &[ConditionRecord]) -> u64 {
+    input.iter().map(ConditionRecord::count_arrangements).sum()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day12, part2)]
+pub fn solve_part2(input: &[ConditionRecord]) -> u64 {
+    input
+        .iter()
+        .map(|record| record.unfold(5).count_arrangements())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 21)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 525152)
+    }
+
+    #[test]
+    fn test_get_arrangements_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(
+            input[5].get_arrangements(),
+            [
+                ".###.##.#...",
+                ".###.##..#..",
+                ".###.##...#.",
+                ".###.##....#",
+                ".###..##.#..",
+                ".###..##..#.",
+                ".###..##...#",
+                ".###...##.#.",
+                ".###...##..#",
+                ".###....##.#"
+            ]
+        );
+
+        for record in &input {
+            let arrangements = record.get_arrangements();
+            assert_eq!(arrangements.len() as u64, record.count_arrangements());
+        }
+    }
+
+    #[test]
+    fn test_generate_input_bad_group() {
+        let error = super::generate_input("???.### 1,0,3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 12, line 1, column 11: expected a positive group size"
+        )
+    }
+}
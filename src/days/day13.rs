@@ -0,0 +1,246 @@
+use std::fmt;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 13;
+
+const ASH: u8 = b'.';
+const ROCK: u8 = b'#';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// A line between two rows.
+    Horizontal,
+    /// A line between two columns.
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Reflection {
+    axis: Axis,
+    index: usize,
+}
+
+impl Reflection {
+    #[inline]
+    pub const fn with(axis: Axis, index: usize) -> Self {
+        Self { axis, index }
+    }
+
+    #[inline]
+    pub const fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// Returns the number of rows above, or columns left of, the line.
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    #[inline]
+    pub const fn get_summary(&self) -> usize {
+        match self.axis {
+            Axis::Horizontal => 100 * self.index,
+            Axis::Vertical => self.index,
+        }
+    }
+}
+
+impl fmt::Display for Reflection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.axis {
+            Axis::Horizontal => write!(f, "between rows {} and {}", self.index, self.index + 1),
+            Axis::Vertical => write!(f, "between columns {} and {}", self.index, self.index + 1),
+        }
+    }
+}
+
+/// A pattern of ash and rocks, with every row and every column stored as a bitmask of rocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+impl Pattern {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            cols: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn rows(&self) -> &[u64] {
+        &self.rows
+    }
+
+    #[inline]
+    pub fn cols(&self) -> &[u64] {
+        &self.cols
+    }
+
+    /// Returns the reflection line across which exactly `smudges` cells differ, checking
+    /// vertical lines first.
+    pub fn find_reflection(&self, smudges: u32) -> Option<Reflection> {
+        find_reflection_index(&self.cols, smudges)
+            .map(|index| Reflection::with(Axis::Vertical, index))
+            .or_else(|| {
+                find_reflection_index(&self.rows, smudges)
+                    .map(|index| Reflection::with(Axis::Horizontal, index))
+            })
+    }
+}
+
+/// Returns the number of lines before the first reflection across which exactly `smudges` bits
+/// of `lines` differ.
+fn find_reflection_index(lines: &[u64], smudges: u32) -> Option<usize> {
+    (1..lines.len()).find(|&index| {
+        lines[..index]
+            .iter()
+            .rev()
+            .zip(&lines[index..])
+            .map(|(line1, line2)| (line1 ^ line2).count_ones())
+            .sum::<u32>()
+            == smudges
+    })
+}
+
+#[aoc_runner_derive::aoc_generator(day13)]
+pub fn generate_input(input: &str) -> Result<Vec<Pattern>, ParseError> {
+    let mut patterns = vec![Pattern::new()];
+
+    for line in input_lines(DAY, input) {
+        let row = line.as_str().as_bytes();
+        if row.is_empty() {
+            patterns.push(Pattern::new());
+            continue;
+        }
+
+        // Checked first so that every byte up to the width limit is a whole character.
+        if let Some(col) = row.iter().position(|&cell| cell != ROCK && cell != ASH) {
+            return Err(line.error(&line.as_str()[col..], "`#` or `.`"));
+        }
+
+        let pattern = patterns.last_mut().unwrap();
+        if pattern.rows.is_empty() {
+            if row.len() > u64::BITS as usize {
+                return Err(line.error(&line.as_str()[64..], "at most 64 columns"));
+            }
+            pattern.cols = vec![0; row.len()];
+        } else if row.len() != pattern.cols.len() {
+            return Err(line.error_at_end("a row as wide as the first one"));
+        }
+
+        let row_no = pattern.rows.len();
+        if row_no == u64::BITS as usize {
+            return Err(line.error(line.as_str(), "at most 64 rows"));
+        }
+        let mut rocks = u64::MIN;
+        for (col, &cell) in row.iter().enumerate() {
+            if cell == ROCK {
+                rocks |= 1 << col;
+                pattern.cols[col] |= 1 << row_no;
+            }
+        }
+
+        pattern.rows.push(rocks);
+    }
+
+    patterns.retain(|pattern| !pattern.rows.is_empty());
+    Ok(patterns)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day13, part1)]
+pub fn solve_part1(input: &[Pattern]) -> Option<usize> {
+    input
+        .iter()
+        .map(|pattern| Some(pattern.find_reflection(0)?.get_summary()))
+        .sum()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day13, part2)]
+pub fn solve_part2(input: &[Pattern]) -> Option<usize> {
+    input
+        .iter()
+        .map(|pattern| Some(pattern.find_reflection(1)?.get_summary()))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis, Reflection};
+
+    const SAMPLE_INPUT: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), Some(405))
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), Some(400))
+    }
+
+    #[test]
+    fn test_find_reflection_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(
+            input[0].find_reflection(0),
+            Some(Reflection::with(Axis::Vertical, 5))
+        );
+        assert_eq!(
+            input[1].find_reflection(0),
+            Some(Reflection::with(Axis::Horizontal, 4))
+        );
+        assert_eq!(
+            input[0].find_reflection(1),
+            Some(Reflection::with(Axis::Horizontal, 3))
+        );
+        assert_eq!(
+            input[1].find_reflection(1),
+            Some(Reflection::with(Axis::Horizontal, 1))
+        );
+        assert_eq!(
+            input[0].find_reflection(2),
+            Some(Reflection::with(Axis::Vertical, 1))
+        )
+    }
+
+    #[test]
+    fn test_generate_input_errors() {
+        let error = super::generate_input(&format!("{}é#", ".".repeat(63)))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "day 13, line 1, column 64: expected `#` or `.`");
+
+        let error = super::generate_input(&".".repeat(65))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 13, line 1, column 65: expected at most 64 columns"
+        )
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use ahash::AHashMap;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 14;
+
+const ROUND_ROCK: u8 = b'O';
+const CUBE_ROCK: u8 = b'#';
+const EMPTY_SPACE: u8 = b'.';
+
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dish {
+    tiles: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Dish {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut tiles = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            if let Some(col) = row
+                .iter()
+                .position(|tile| ![ROUND_ROCK, CUBE_ROCK, EMPTY_SPACE].contains(tile))
+            {
+                return Err(line.error(&line.as_str()[col..], "`O`, `#` or `.`"));
+            }
+
+            tiles.extend_from_slice(row);
+            height += 1;
+        }
+
+        Ok(Self {
+            tiles,
+            width,
+            height,
+        })
+    }
+
+    /// Rolls every round rock as far as it goes towards `direction`.
+    pub fn tilt(&mut self, direction: Direction) {
+        let (width, height) = (self.width, self.height);
+        match direction {
+            Direction::North => {
+                for col in 0..width {
+                    self.tilt_line(height, |row| row * width + col);
+                }
+            }
+            Direction::West => {
+                for row in 0..height {
+                    self.tilt_line(width, |col| row * width + col);
+                }
+            }
+            Direction::South => {
+                for col in 0..width {
+                    self.tilt_line(height, |row| (height - 1 - row) * width + col);
+                }
+            }
+            Direction::East => {
+                for row in 0..height {
+                    self.tilt_line(width, |col| row * width + width - 1 - col);
+                }
+            }
+        }
+    }
+
+    /// Rolls the round rocks along a line of `len` tiles towards its first one, where
+    /// `get_index` maps a position on the line to a tile index.
+    fn tilt_line(&mut self, len: usize, get_index: impl Fn(usize) -> usize) {
+        let mut free_position = usize::MIN;
+        for position in 0..len {
+            let index = get_index(position);
+            match self.tiles[index] {
+                CUBE_ROCK => free_position = position + 1,
+                ROUND_ROCK => {
+                    self.tiles[index] = EMPTY_SPACE;
+                    self.tiles[get_index(free_position)] = ROUND_ROCK;
+                    free_position += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Tilts the dish north, west, south and then east.
+    pub fn spin_cycle(&mut self) {
+        for direction in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            self.tilt(direction);
+        }
+    }
+
+    /// Runs `count` spin cycles, skipping ahead once the dish returns to a state it was already
+    /// in.
+    pub fn spin_cycles(&mut self, count: usize) {
+        let mut first_seen = AHashMap::new();
+        let mut states = Vec::new();
+
+        for i in 0..count {
+            if let Some(&cycle_start) = first_seen.get(&self.tiles) {
+                let cycle_len = i - cycle_start;
+                self.tiles
+                    .clone_from(&states[cycle_start + (count - i) % cycle_len]);
+                return;
+            }
+
+            first_seen.insert(self.tiles.clone(), i);
+            states.push(self.tiles.clone());
+            self.spin_cycle();
+        }
+    }
+
+    /// Returns the total load on the north support beams.
+    pub fn get_north_load(&self) -> usize {
+        self.tiles
+            .chunks_exact(self.width.max(1))
+            .zip((1..=self.height).rev())
+            .map(|(row, load)| row.iter().filter(|&&tile| tile == ROUND_ROCK).count() * load)
+            .sum()
+    }
+}
+
+impl fmt::Display for Dish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.tiles.chunks_exact(self.width.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            f.write_str(std::str::from_utf8(row).unwrap())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day14)]
+pub fn generate_input(input: &str) -> Result<Dish, ParseError> {
+    Dish::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day14, part1)]
+pub fn solve_part1(input: &Dish) -> usize {
+    let mut dish = input.clone();
+    dish.tilt(Direction::North);
+    dish.get_north_load()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day14, part2)]
+pub fn solve_part2(input: &Dish) -> usize {
+    let mut dish = input.clone();
+    dish.spin_cycles(SPIN_CYCLES);
+    dish.get_north_load()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 136)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 64)
+    }
+
+    #[test]
+    fn test_tilt_sample() {
+        let mut dish = super::generate_input(SAMPLE_INPUT).unwrap();
+        dish.tilt(super::Direction::North);
+        assert_eq!(
+            dish.to_string(),
+            "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#...."
+        )
+    }
+
+    #[test]
+    fn test_spin_cycles_sample() {
+        let mut dish = super::generate_input(SAMPLE_INPUT).unwrap();
+        dish.spin_cycle();
+        assert_eq!(
+            dish.to_string(),
+            ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#...."
+        );
+
+        let mut expected = super::generate_input(SAMPLE_INPUT).unwrap();
+        for _ in 0..3 {
+            expected.spin_cycle();
+        }
+        let mut dish = super::generate_input(SAMPLE_INPUT).unwrap();
+        dish.spin_cycles(3);
+        assert_eq!(dish, expected);
+
+        for _ in 0..20 {
+            expected.spin_cycle();
+        }
+        dish.spin_cycles(20);
+        assert_eq!(dish, expected)
+    }
+}
@@ -0,0 +1,410 @@
+use std::fmt;
+
+use crate::error::{InputLine, ParseError};
+
+const DAY: u8 = 15;
+
+const BOX_COUNT: usize = 256;
+
+/// Runs the Holiday ASCII String Helper algorithm on `s`.
+pub fn hash(s: &str) -> u8 {
+    s.bytes().fold(u8::MIN, |value, byte| {
+        value.wrapping_add(byte).wrapping_mul(17)
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// `-`: take the lens with the label out of its box.
+    Remove,
+    /// `=`: put a lens with the label and this focal length in its box.
+    Insert(u8),
+}
+
+/// A step of the initialization sequence, which keeps its text as written.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Step {
+    text: String,
+    label: String,
+    operation: Operation,
+}
+
+impl Step {
+    /// Returns the step written the usual way, e.g. `ab=5` or `ab-`.
+    pub fn with(label: String, operation: Operation) -> Self {
+        let text = match operation {
+            Operation::Remove => format!("{label}-"),
+            Operation::Insert(focal_length) => format!("{label}={focal_length}"),
+        };
+
+        Self {
+            text,
+            label,
+            operation,
+        }
+    }
+
+    pub fn parse(line: &InputLine, step: &str) -> Result<Self, ParseError> {
+        let Some(i) = step.find(['-', '=']) else {
+            let expected = "a label followed by `-` or `=` and a focal length";
+            return Err(line.error(&step[step.len()..], expected));
+        };
+
+        let (label, operation) = step.split_at(i);
+        let operation = if operation == "-" {
+            Operation::Remove
+        } else if let Some(focal_length) = operation.strip_prefix('=') {
+            match line.parse(focal_length, "a focal length from 1 to 9")? {
+                focal_length @ 1..=9 => Operation::Insert(focal_length),
+                _ => return Err(line.error(focal_length, "a focal length from 1 to 9")),
+            }
+        } else {
+            return Err(line.error(&operation[1..], "the end of the step"));
+        };
+
+        if label.is_empty() {
+            return Err(line.error(step, "a label"));
+        }
+        if let Some(i) = label.bytes().position(|byte| !byte.is_ascii_alphabetic()) {
+            return Err(line.error(&label[i..], "a letter"));
+        }
+
+        Ok(Self {
+            text: step.to_owned(),
+            label: label.to_owned(),
+            operation,
+        })
+    }
+
+    /// Returns the step as written in the input.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    #[inline]
+    pub const fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Returns the index of the box the step operates on.
+    #[inline]
+    pub fn get_box_index(&self) -> u8 {
+        hash(&self.label)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Lens {
+    label: String,
+    focal_length: u8,
+}
+
+impl Lens {
+    #[inline]
+    pub const fn with(label: String, focal_length: u8) -> Self {
+        Self {
+            label,
+            focal_length,
+        }
+    }
+
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    #[inline]
+    pub const fn focal_length(&self) -> u8 {
+        self.focal_length
+    }
+}
+
+impl fmt::Display for Lens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{} {}]", self.label, self.focal_length)
+    }
+}
+
+/// The 256 boxes of lenses, in the order they were put in each box.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LensLibrary {
+    boxes: Vec<Vec<Lens>>,
+}
+
+impl Default for LensLibrary {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LensLibrary {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            boxes: vec![Vec::new(); BOX_COUNT],
+        }
+    }
+
+    /// Returns the lenses in the box at `index`, from front to back.
+    #[inline]
+    pub fn get_box(&self, index: u8) -> &[Lens] {
+        &self.boxes[usize::from(index)]
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        let lenses = &mut self.boxes[usize::from(step.get_box_index())];
+        let position = lenses.iter().position(|lens| lens.label == step.label);
+
+        match (step.operation, position) {
+            (Operation::Remove, Some(i)) => {
+                lenses.remove(i);
+            }
+            (Operation::Remove, None) => {}
+            (Operation::Insert(focal_length), Some(i)) => lenses[i].focal_length = focal_length,
+            (Operation::Insert(focal_length), None) => {
+                lenses.push(Lens::with(step.label.clone(), focal_length));
+            }
+        }
+    }
+
+    /// Applies every step in order, recording the library after each of them.
+    pub fn trace(&mut self, steps: &[Step]) -> LibraryTrace {
+        let mut snapshots = Vec::with_capacity(steps.len());
+        for step in steps {
+            self.apply(step);
+            snapshots.push((step.clone(), self.clone()));
+        }
+
+        LibraryTrace { snapshots }
+    }
+
+    pub fn get_focusing_power(&self) -> usize {
+        self.boxes
+            .iter()
+            .zip(1..)
+            .flat_map(|(lenses, box_no)| {
+                lenses
+                    .iter()
+                    .zip(1..)
+                    .map(move |(lens, slot_no)| box_no * slot_no * usize::from(lens.focal_length))
+            })
+            .sum()
+    }
+}
+
+/// Lists the boxes that hold lenses, one per line, e.g. `Box 3: [ot 7] [ab 5] [pc 6]`.
+impl fmt::Display for LensLibrary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut is_first = true;
+        for (index, lenses) in self.boxes.iter().enumerate() {
+            if lenses.is_empty() {
+                continue;
+            }
+
+            if !is_first {
+                writeln!(f)?;
+            }
+            is_first = false;
+
+            write!(f, "Box {index}:")?;
+            for lens in lenses {
+                write!(f, " {lens}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of a library after each step applied to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LibraryTrace {
+    snapshots: Vec<(Step, LensLibrary)>,
+}
+
+impl LibraryTrace {
+    #[inline]
+    pub fn snapshots(&self) -> &[(Step, LensLibrary)] {
+        &self.snapshots
+    }
+}
+
+impl fmt::Display for LibraryTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (step, library)) in self.snapshots.iter().enumerate() {
+            if i > 0 {
+                write!(f, "\n\n")?;
+            }
+
+            write!(f, "After \"{step}\":")?;
+            if library.boxes.iter().any(|lenses| !lenses.is_empty()) {
+                write!(f, "\n{library}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the initialization sequence with its line breaks taken out, as the puzzle says to
+/// ignore them.
+fn join_lines(input: &str) -> String {
+    input.replace('\n', "")
+}
+
+/// Returns the comma-separated steps of the sequence as written, skipping empty ones.
+fn split_steps(sequence: &str) -> impl Iterator<Item = &str> {
+    sequence.split(',').filter(|step| !step.is_empty())
+}
+
+/// Parses the initialization sequence. Line breaks are ignored, so errors point into the
+/// sequence as a single line.
+#[aoc_runner_derive::aoc_generator(day15, part2)]
+pub fn generate_input(input: &str) -> Result<Vec<Step>, ParseError> {
+    let sequence = join_lines(input);
+    let line = InputLine::new(DAY, 1, &sequence);
+    split_steps(&sequence)
+        .map(|step| Step::parse(&line, step))
+        .collect()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day15, part1)]
+pub fn solve_part1(input: &str) -> usize {
+    split_steps(&join_lines(input))
+        .map(|step| usize::from(hash(step)))
+        .sum()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day15, part2)]
+pub fn solve_part2(input: &[Step]) -> usize {
+    let mut library = LensLibrary::new();
+    for step in input {
+        library.apply(step);
+    }
+
+    library.get_focusing_power()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        assert_eq!(super::solve_part1(SAMPLE_INPUT), 1320)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 145)
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(super::hash("HASH"), 52);
+        assert_eq!(super::hash("rn"), 0);
+        assert_eq!(super::hash("qp"), 1)
+    }
+
+    #[test]
+    fn test_trace_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let mut library = super::LensLibrary::new();
+        let trace = library.trace(&input);
+        assert_eq!(
+            trace.to_string(),
+            "After \"rn=1\":
+Box 0: [rn 1]
+
+After \"cm-\":
+Box 0: [rn 1]
+
+After \"qp=3\":
+Box 0: [rn 1]
+Box 1: [qp 3]
+
+After \"cm=2\":
+Box 0: [rn 1] [cm 2]
+Box 1: [qp 3]
+
+After \"qp-\":
+Box 0: [rn 1] [cm 2]
+
+After \"pc=4\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4]
+
+After \"ot=9\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4] [ot 9]
+
+After \"ab=5\":
+Box 0: [rn 1] [cm 2]
+Box 3: [pc 4] [ot 9] [ab 5]
+
+After \"pc-\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 9] [ab 5]
+
+After \"pc=6\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 9] [ab 5] [pc 6]
+
+After \"ot=7\":
+Box 0: [rn 1] [cm 2]
+Box 3: [ot 7] [ab 5] [pc 6]"
+        );
+        assert_eq!(library.get_box(3).len(), 3);
+        assert_eq!(library.get_box(3)[0].label(), "ot");
+        assert_eq!(library.get_box(3)[0].focal_length(), 7);
+        assert_eq!(library.get_focusing_power(), 145)
+    }
+
+    #[test]
+    fn test_generate_input_bad_focal_length() {
+        let error = super::generate_input("rn=1,cm=0").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 15, line 1, column 9: expected a focal length from 1 to 9"
+        );
+
+        let error = super::generate_input("rn=1,=5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 15, line 1, column 6: expected a label"
+        )
+    }
+
+    #[test]
+    fn test_solve_part1_hashes_steps_as_written() {
+        for (step, expected) in [("ab=5", 197), ("ab=05", 245), ("ab=+5", 80), ("=", 13)] {
+            assert_eq!(super::solve_part1(step), expected);
+        }
+    }
+
+    #[test]
+    fn test_step_wrapped_across_lines() {
+        let input = SAMPLE_INPUT.replace("ot=9", "o\nt=9") + "\n";
+        assert_eq!(super::solve_part1(&input), 1320);
+
+        let steps = super::generate_input(&input).unwrap();
+        assert_eq!(steps[6].as_str(), "ot=9");
+        assert_eq!(super::solve_part2(&steps), 145)
+    }
+}
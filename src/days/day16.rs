@@ -0,0 +1,294 @@
+use std::fmt;
+
+use rayon::prelude::*;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 16;
+
+pub type Coords = (usize, usize);
+
+const EMPTY_SPACE: u8 = b'.';
+const MIRROR_SLASH: u8 = b'/';
+const MIRROR_BACKSLASH: u8 = b'\\';
+const SPLITTER_VERTICAL: u8 = b'|';
+const SPLITTER_HORIZONTAL: u8 = b'-';
+
+const ENERGIZED: u8 = b'#';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    #[inline]
+    const fn get_bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Returns the direction a beam travelling this way leaves `tile` in, and the second one if
+    /// the tile splits it.
+    const fn get_next(self, tile: u8) -> (Self, Option<Self>) {
+        use Direction::*;
+
+        match (tile, self) {
+            (MIRROR_SLASH, Up) | (MIRROR_BACKSLASH, Down) => (Right, None),
+            (MIRROR_SLASH, Right) | (MIRROR_BACKSLASH, Left) => (Up, None),
+            (MIRROR_SLASH, Down) | (MIRROR_BACKSLASH, Up) => (Left, None),
+            (MIRROR_SLASH, Left) | (MIRROR_BACKSLASH, Right) => (Down, None),
+            (SPLITTER_VERTICAL, Right | Left) => (Up, Some(Down)),
+            (SPLITTER_HORIZONTAL, Up | Down) => (Left, Some(Right)),
+            _ => (self, None),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Contraption {
+    tiles: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Contraption {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut tiles = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            if let Some(col) = row.iter().position(|tile| {
+                ![
+                    EMPTY_SPACE,
+                    MIRROR_SLASH,
+                    MIRROR_BACKSLASH,
+                    SPLITTER_VERTICAL,
+                    SPLITTER_HORIZONTAL,
+                ]
+                .contains(tile)
+            }) {
+                return Err(line.error(&line.as_str()[col..], "`.`, `/`, `\\`, `|` or `-`"));
+            }
+
+            tiles.extend_from_slice(row);
+            height += 1;
+        }
+
+        Ok(Self {
+            tiles,
+            width,
+            height,
+        })
+    }
+
+    fn get_neighbour(&self, (row, col): Coords, direction: Direction) -> Option<Coords> {
+        match direction {
+            Direction::Up if row > 0 => Some((row - 1, col)),
+            Direction::Right if col + 1 < self.width => Some((row, col + 1)),
+            Direction::Down if row + 1 < self.height => Some((row + 1, col)),
+            Direction::Left if col > 0 => Some((row, col - 1)),
+            _ => None,
+        }
+    }
+
+    /// Follows a beam entering the tile at `start` heading in `direction`, through every
+    /// mirror and splitter, until each of its parts leaves the grid or repeats itself. A beam
+    /// starting outside the grid energizes nothing.
+    pub fn trace_beam(&self, start: Coords, direction: Direction) -> EnergizedMap {
+        // The directions beams have already left each tile in.
+        let mut visited = vec![0u8; self.tiles.len()];
+        let mut beams = Vec::new();
+        if start.0 < self.height && start.1 < self.width {
+            beams.push((start, direction));
+        }
+
+        while let Some((tile @ (row, col), direction)) = beams.pop() {
+            let index = row * self.width + col;
+            let (next_direction, split_direction) = direction.get_next(self.tiles[index]);
+
+            for next_direction in [Some(next_direction), split_direction]
+                .into_iter()
+                .flatten()
+            {
+                if visited[index] & next_direction.get_bit() != 0 {
+                    continue;
+                }
+
+                visited[index] |= next_direction.get_bit();
+                if let Some(next_tile) = self.get_neighbour(tile, next_direction) {
+                    beams.push((next_tile, next_direction));
+                }
+            }
+        }
+
+        EnergizedMap {
+            is_energized: visited
+                .into_iter()
+                .map(|directions| directions != 0)
+                .collect(),
+            width: self.width,
+        }
+    }
+
+    /// Returns every tile on the edge of the grid together with the direction into the grid.
+    pub fn get_edge_entries(&self) -> Vec<(Coords, Direction)> {
+        if self.width == 0 || self.height == 0 {
+            return Vec::new();
+        }
+
+        let (last_row, last_col) = (self.height - 1, self.width - 1);
+        (0..self.width)
+            .flat_map(|col| {
+                [
+                    ((0, col), Direction::Down),
+                    ((last_row, col), Direction::Up),
+                ]
+            })
+            .chain((0..self.height).flat_map(|row| {
+                [
+                    ((row, 0), Direction::Right),
+                    ((row, last_col), Direction::Left),
+                ]
+            }))
+            .collect()
+    }
+
+    /// Returns the most tiles any beam entering from the edge energizes, trying every entry in
+    /// parallel.
+    pub fn get_max_energized(&self) -> usize {
+        self.get_edge_entries()
+            .into_par_iter()
+            .map(|(start, direction)| self.trace_beam(start, direction).count())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// The tiles a beam passed through.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnergizedMap {
+    is_energized: Vec<bool>,
+    width: usize,
+}
+
+impl EnergizedMap {
+    #[inline]
+    pub fn is_energized(&self, (row, col): Coords) -> bool {
+        self.is_energized[row * self.width + col]
+    }
+
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.is_energized
+            .iter()
+            .filter(|&&is_energized| is_energized)
+            .count()
+    }
+}
+
+/// Draws energized tiles as `#` and the rest as `.`.
+impl fmt::Display for EnergizedMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self
+            .is_energized
+            .chunks_exact(self.width.max(1))
+            .enumerate()
+        {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            for &is_energized in row {
+                let tile = if is_energized { ENERGIZED } else { EMPTY_SPACE };
+                write!(f, "{}", char::from(tile))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day16)]
+pub fn generate_input(input: &str) -> Result<Contraption, ParseError> {
+    Contraption::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day16, part1)]
+pub fn solve_part1(input: &Contraption) -> usize {
+    input.trace_beam((0, 0), Direction::Right).count()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day16, part2)]
+pub fn solve_part2(input: &Contraption) -> usize {
+    input.get_max_energized()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 46)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 51)
+    }
+
+    #[test]
+    fn test_solve_empty_grid() {
+        for input in ["", "\n\n"] {
+            let input = super::generate_input(input).unwrap();
+            assert_eq!(input.get_edge_entries(), []);
+            assert_eq!(super::solve_part1(&input), 0);
+            assert_eq!(super::solve_part2(&input), 0);
+        }
+    }
+
+    #[test]
+    fn test_trace_beam_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let energized_map = input.trace_beam((0, 0), super::Direction::Right);
+        assert_eq!(
+            energized_map.to_string(),
+            "######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#.."
+        );
+        assert_eq!(input.trace_beam((0, 3), super::Direction::Down).count(), 51)
+    }
+}
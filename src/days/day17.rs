@@ -0,0 +1,280 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 17;
+
+pub type Coords = (usize, usize);
+
+const DIRECTIONS: usize = 4;
+const NO_STATE: usize = usize::MAX;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeatMap {
+    heat_losses: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+/// A path from the top-left to the bottom-right block, with the heat lost along it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CruciblePath {
+    blocks: Vec<Coords>,
+    heat_loss: u32,
+}
+
+impl CruciblePath {
+    /// Returns the blocks along the path, starting with the top-left one.
+    #[inline]
+    pub fn blocks(&self) -> &[Coords] {
+        &self.blocks
+    }
+
+    /// Returns the heat lost in every block entered, that is, all but the first.
+    #[inline]
+    pub const fn heat_loss(&self) -> u32 {
+        self.heat_loss
+    }
+}
+
+impl HeatMap {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut heat_losses = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            for (col, &block) in row.iter().enumerate() {
+                if !block.is_ascii_digit() {
+                    return Err(line.error(&line.as_str()[col..], "a digit"));
+                }
+
+                heat_losses.push(block - b'0');
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            heat_losses,
+            width,
+            height,
+        })
+    }
+
+    /// Returns the block one step from `index` in `direction` (up, right, down or left).
+    fn get_neighbour(&self, index: usize, direction: usize) -> Option<usize> {
+        let (row, col) = (index / self.width, index % self.width);
+        match direction {
+            0 if row > 0 => Some(index - self.width),
+            1 if col + 1 < self.width => Some(index + 1),
+            2 if row + 1 < self.height => Some(index + self.width),
+            3 if col > 0 => Some(index - 1),
+            _ => None,
+        }
+    }
+
+    /// Finds the path from the top-left to the bottom-right block that loses the least heat,
+    /// for a crucible that moves between `min_run` and `max_run` blocks in a straight line
+    /// before turning or stopping.
+    ///
+    /// Runs Dijkstra's algorithm over (block, direction, run length) states.
+    pub fn find_path(&self, min_run: usize, max_run: usize) -> Option<CruciblePath> {
+        if self.heat_losses.is_empty() || max_run == 0 || min_run > max_run {
+            return None;
+        }
+
+        let end = self.heat_losses.len() - 1;
+        if end == 0 {
+            return Some(CruciblePath {
+                blocks: vec![(0, 0)],
+                heat_loss: u32::MIN,
+            });
+        }
+
+        let get_state = |index: usize, direction: usize, run: usize| {
+            (index * DIRECTIONS + direction) * max_run + run - 1
+        };
+        let state_count = self.heat_losses.len() * DIRECTIONS * max_run;
+        let mut heat_losses = vec![u32::MAX; state_count];
+        let mut prev_states = vec![NO_STATE; state_count];
+        let mut queue = BinaryHeap::new();
+
+        for direction in 0..DIRECTIONS {
+            if let Some(next) = self.get_neighbour(0, direction) {
+                let state = get_state(next, direction, 1);
+                heat_losses[state] = u32::from(self.heat_losses[next]);
+                queue.push(Reverse((heat_losses[state], state)));
+            }
+        }
+
+        while let Some(Reverse((heat_loss, state))) = queue.pop() {
+            if heat_loss > heat_losses[state] {
+                continue;
+            }
+
+            let run = state % max_run + 1;
+            let direction = state / max_run % DIRECTIONS;
+            let index = state / max_run / DIRECTIONS;
+            if index == end && run >= min_run {
+                return Some(self.get_path(state, max_run, &prev_states, heat_loss));
+            }
+
+            let next_moves = [
+                (direction, run + 1),
+                ((direction + 1) % DIRECTIONS, 1),
+                ((direction + DIRECTIONS - 1) % DIRECTIONS, 1),
+            ];
+            for (next_direction, next_run) in next_moves {
+                let is_turn = next_direction != direction;
+                if (is_turn && run < min_run) || next_run > max_run {
+                    continue;
+                }
+
+                let Some(next) = self.get_neighbour(index, next_direction) else {
+                    continue;
+                };
+
+                let next_state = get_state(next, next_direction, next_run);
+                let next_heat_loss = heat_loss + u32::from(self.heat_losses[next]);
+                if next_heat_loss < heat_losses[next_state] {
+                    heat_losses[next_state] = next_heat_loss;
+                    prev_states[next_state] = state;
+                    queue.push(Reverse((next_heat_loss, next_state)));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn get_path(
+        &self,
+        mut state: usize,
+        max_run: usize,
+        prev_states: &[usize],
+        heat_loss: u32,
+    ) -> CruciblePath {
+        let mut blocks = vec![(0, 0)];
+        while state != NO_STATE {
+            let index = state / max_run / DIRECTIONS;
+            blocks.push((index / self.width, index % self.width));
+            state = prev_states[state];
+        }
+
+        blocks[1..].reverse();
+        CruciblePath { blocks, heat_loss }
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day17)]
+pub fn generate_input(input: &str) -> Result<HeatMap, ParseError> {
+    HeatMap::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day17, part1)]
+pub fn solve_part1(input: &HeatMap) -> Option<u32> {
+    Some(input.find_path(1, 3)?.heat_loss())
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day17, part2)]
+pub fn solve_part2(input: &HeatMap) -> Option<u32> {
+    Some(input.find_path(4, 10)?.heat_loss())
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    /// Checks that `path` moves one block at a time, never reverses, keeps its straight runs
+    /// between `min_run` and `max_run` blocks, and loses the heat it claims to.
+    fn assert_valid_path(
+        input: &super::HeatMap,
+        path: &super::CruciblePath,
+        min_run: usize,
+        max_run: usize,
+    ) {
+        let blocks = path.blocks();
+        assert_eq!(blocks.first(), Some(&(0, 0)));
+        assert_eq!(blocks.last(), Some(&(input.height - 1, input.width - 1)));
+
+        let steps = blocks
+            .windows(2)
+            .map(|window| {
+                let ((row1, col1), (row2, col2)) = (window[0], window[1]);
+                assert_eq!(row1.abs_diff(row2) + col1.abs_diff(col2), 1);
+                (row2 as isize - row1 as isize, col2 as isize - col1 as isize)
+            })
+            .collect::<Vec<_>>();
+        let runs = steps.chunk_by(|step1, step2| step1 == step2);
+        for (run, next_run) in runs.clone().zip(runs.skip(1)) {
+            assert_ne!((run[0].0 + next_run[0].0, run[0].1 + next_run[0].1), (0, 0));
+        }
+        for run in steps.chunk_by(|step1, step2| step1 == step2) {
+            assert!((min_run..=max_run).contains(&run.len()));
+        }
+
+        let heat_loss = blocks[1..]
+            .iter()
+            .map(|&(row, col)| u32::from(input.heat_losses[row * input.width + col]))
+            .sum::<u32>();
+        assert_eq!(path.heat_loss(), heat_loss)
+    }
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), Some(102))
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), Some(94))
+    }
+
+    #[test]
+    fn test_solve_part2_sample_2() {
+        let input = super::generate_input(
+            "111111111111
+999999999991
+999999999991
+999999999991
+999999999991",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), Some(71))
+    }
+
+    #[test]
+    fn test_find_path_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let path = input.find_path(1, 3).unwrap();
+        assert_valid_path(&input, &path, 1, 3);
+
+        let path = input.find_path(4, 10).unwrap();
+        assert_valid_path(&input, &path, 4, 10)
+    }
+}
@@ -0,0 +1,311 @@
+use std::{error, fmt};
+
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 18;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    #[inline]
+    const fn get_offset(self) -> (i64, i64) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Right => (0, 1),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DigStep {
+    direction: Direction,
+    length: u32,
+}
+
+impl DigStep {
+    #[inline]
+    pub const fn with(direction: Direction, length: u32) -> Self {
+        Self { direction, length }
+    }
+
+    #[inline]
+    pub const fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    #[inline]
+    pub const fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Parses the `R 6` form, a direction letter and a length.
+    pub fn parse_plain(line: &InputLine, s: &str) -> Result<Self, ParseError> {
+        let (direction, length) = line.split_once(s, ' ', "a direction and a length")?;
+        let direction = match direction {
+            "U" => Direction::Up,
+            "R" => Direction::Right,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            _ => return Err(line.error(direction, "`U`, `R`, `D` or `L`")),
+        };
+        let length = line.parse(length, "a length")?;
+
+        Ok(Self::with(direction, length))
+    }
+
+    /// Parses the `(#70c710)` form, five hex digits of length followed by a direction digit.
+    pub fn parse_color(line: &InputLine, s: &str) -> Result<Self, ParseError> {
+        let expected = "a colour like `(#70c710)`";
+        let color = s
+            .strip_prefix("(#")
+            .and_then(|color| color.strip_suffix(')'))
+            .filter(|color| color.len() == 6 && color.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| line.error(s, expected))?;
+
+        let (length, direction) = color.split_at(5);
+        let direction = match direction {
+            "0" => Direction::Right,
+            "1" => Direction::Down,
+            "2" => Direction::Left,
+            "3" => Direction::Up,
+            _ => return Err(line.error(direction, "a direction digit from 0 to 3")),
+        };
+        let length = u32::from_str_radix(length, 16).map_err(|_| line.error(length, expected))?;
+
+        Ok(Self::with(direction, length))
+    }
+}
+
+/// A dig plan read both ways: by its directions and lengths, and by its colours.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DigPlan {
+    steps: Vec<DigStep>,
+    color_steps: Vec<DigStep>,
+}
+
+impl DigPlan {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut steps = Vec::new();
+        let mut color_steps = Vec::new();
+
+        for line in input_lines(DAY, s) {
+            let (step, color) = line
+                .as_str()
+                .rsplit_once(' ')
+                .ok_or_else(|| line.error_at_end("a step followed by a colour"))?;
+
+            steps.push(DigStep::parse_plain(&line, step)?);
+            color_steps.push(DigStep::parse_color(&line, color)?);
+        }
+
+        Ok(Self { steps, color_steps })
+    }
+
+    #[inline]
+    pub fn steps(&self) -> &[DigStep] {
+        &self.steps
+    }
+
+    #[inline]
+    pub fn color_steps(&self) -> &[DigStep] {
+        &self.color_steps
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LagoonError {
+    /// The trench does not end where it started.
+    OpenTrench { end: (i128, i128) },
+    /// The trench runs over itself, so it does not enclose a single lagoon.
+    OverlappingTrench,
+    /// The lagoon holds more lava than fits in a u64.
+    VolumeOutOfRange,
+}
+
+impl fmt::Display for LagoonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpenTrench { end: (row, col) } => {
+                write!(
+                    f,
+                    "the trench ends at ({row}, {col}) instead of where it started"
+                )
+            }
+            Self::OverlappingTrench => write!(f, "the trench runs over itself"),
+            Self::VolumeOutOfRange => write!(f, "the lagoon volume does not fit in a u64"),
+        }
+    }
+}
+
+impl error::Error for LagoonError {}
+
+/// Returns how many cubic metres of lava the lagoon dug by `steps` holds, counting the
+/// trench itself.
+///
+/// The area inside the trench comes from the shoelace formula, and Pick's theorem turns it into
+/// the number of interior points, so the cost only depends on the number of steps. The sums are
+/// kept in i128, which holds them for any plan of u32 lengths that fits in memory.
+pub fn get_lagoon_volume(steps: &[DigStep]) -> Result<u64, LagoonError> {
+    let mut double_area = 0i128;
+    let mut boundary_points = 0i128;
+    let (mut row, mut col) = (0i128, 0i128);
+
+    for step in steps {
+        let (row_offset, col_offset) = step.direction.get_offset();
+        let length = i128::from(step.length);
+        let (next_row, next_col) = (
+            row + i128::from(row_offset) * length,
+            col + i128::from(col_offset) * length,
+        );
+
+        double_area += col * next_row - next_col * row;
+        boundary_points += length;
+        (row, col) = (next_row, next_col);
+    }
+
+    if (row, col) != (0, 0) {
+        return Err(LagoonError::OpenTrench { end: (row, col) });
+    }
+
+    // Pick's theorem: area = interior + boundary / 2 - 1
+    let double_interior_points = double_area.abs() + 2 - boundary_points;
+    if double_interior_points < 0 {
+        return Err(LagoonError::OverlappingTrench);
+    }
+
+    u64::try_from(double_interior_points / 2 + boundary_points)
+        .map_err(|_| LagoonError::VolumeOutOfRange)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day18)]
+pub fn generate_input(input: &str) -> Result<DigPlan, ParseError> {
+    DigPlan::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day18, part1)]
+pub fn solve_part1(input: &DigPlan) -> Result<u64, LagoonError> {
+    get_lagoon_volume(input.steps())
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day18, part2)]
+pub fn solve_part2(input: &DigPlan) -> Result<u64, LagoonError> {
+    get_lagoon_volume(input.color_steps())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigStep, Direction};
+
+    const SAMPLE_INPUT: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), Ok(62))
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), Ok(952408144115))
+    }
+
+    #[test]
+    fn test_parse_color_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(
+            input.color_steps()[..3],
+            [
+                DigStep::with(Direction::Right, 461937),
+                DigStep::with(Direction::Down, 56407),
+                DigStep::with(Direction::Right, 356671)
+            ]
+        )
+    }
+
+    #[test]
+    fn test_get_lagoon_volume_square() {
+        let steps = [
+            DigStep::with(Direction::Right, 2),
+            DigStep::with(Direction::Down, 2),
+            DigStep::with(Direction::Left, 2),
+            DigStep::with(Direction::Up, 2),
+        ];
+        assert_eq!(super::get_lagoon_volume(&steps), Ok(9));
+
+        let square = |length| {
+            [
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+                Direction::Up,
+            ]
+            .map(|direction| DigStep::with(direction, length))
+        };
+        assert_eq!(
+            super::get_lagoon_volume(&square(3_000_000_000)),
+            Ok(3_000_000_001 * 3_000_000_001)
+        );
+        assert_eq!(
+            super::get_lagoon_volume(&square(u32::MAX)),
+            Err(super::LagoonError::VolumeOutOfRange)
+        )
+    }
+
+    #[test]
+    fn test_get_lagoon_volume_errors() {
+        use super::LagoonError;
+
+        let steps = [
+            DigStep::with(Direction::Right, 2),
+            DigStep::with(Direction::Down, 2),
+            DigStep::with(Direction::Left, 2),
+        ];
+        assert_eq!(
+            super::get_lagoon_volume(&steps),
+            Err(LagoonError::OpenTrench { end: (2, 0) })
+        );
+
+        let steps = [
+            DigStep::with(Direction::Right, 2),
+            DigStep::with(Direction::Left, 2),
+        ];
+        assert_eq!(
+            super::get_lagoon_volume(&steps),
+            Err(LagoonError::OverlappingTrench)
+        )
+    }
+
+    #[test]
+    fn test_generate_input_bad_color() {
+        let error = super::generate_input("R 6 (#70c71)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 18, line 1, column 5: expected a colour like `(#70c710)`"
+        )
+    }
+}
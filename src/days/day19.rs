@@ -0,0 +1,600 @@
+use std::ops::Range;
+
+use ahash::AHashMap;
+
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 19;
+
+pub type WorkflowId = u32;
+
+const START_WORKFLOW: &str = "in";
+const MAX_RATING: u16 = 4000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    const ALL: [Self; 4] = [Self::X, Self::M, Self::A, Self::S];
+
+    #[inline]
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Less,
+    Greater,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Condition {
+    category: Category,
+    comparison: Comparison,
+    value: u16,
+}
+
+impl Condition {
+    #[inline]
+    pub const fn with(category: Category, comparison: Comparison, value: u16) -> Self {
+        Self {
+            category,
+            comparison,
+            value,
+        }
+    }
+
+    #[inline]
+    pub const fn matches(&self, part: &Part) -> bool {
+        let rating = part.ratings[self.category.index()];
+        match self.comparison {
+            Comparison::Less => rating < self.value,
+            Comparison::Greater => rating > self.value,
+        }
+    }
+
+    /// Splits `part_box` into the parts that match the condition and the ones that do not.
+    pub fn split(&self, part_box: &PartBox) -> (PartBox, PartBox) {
+        let range = &part_box.ranges[self.category.index()];
+        let (matching, rest) = match self.comparison {
+            Comparison::Less => (
+                range.start..range.end.min(self.value),
+                range.start.max(self.value)..range.end,
+            ),
+            Comparison::Greater => (
+                range.start.max(self.value + 1)..range.end,
+                range.start..range.end.min(self.value + 1),
+            ),
+        };
+
+        let mut matching_box = part_box.clone();
+        let mut rest_box = part_box.clone();
+        matching_box.ranges[self.category.index()] = matching;
+        rest_box.ranges[self.category.index()] = rest;
+        (matching_box, rest_box)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Target {
+    Accept,
+    #[default]
+    Reject,
+    Workflow(WorkflowId),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Workflow {
+    rules: Vec<(Condition, Target)>,
+    fallback: Target,
+}
+
+impl Workflow {
+    #[inline]
+    pub fn rules(&self) -> &[(Condition, Target)] {
+        &self.rules
+    }
+
+    /// Returns where parts that match no rule go.
+    #[inline]
+    pub const fn fallback(&self) -> Target {
+        self.fallback
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Part {
+    ratings: [u16; 4],
+}
+
+impl Part {
+    #[inline]
+    pub const fn with(ratings: [u16; 4]) -> Self {
+        Self { ratings }
+    }
+
+    #[inline]
+    pub const fn get_rating(&self, category: Category) -> u16 {
+        self.ratings[category.index()]
+    }
+
+    #[inline]
+    pub fn get_rating_sum(&self) -> u32 {
+        self.ratings.iter().copied().map(u32::from).sum()
+    }
+}
+
+/// Every part whose `x`, `m`, `a` and `s` ratings fall in the given ranges.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PartBox {
+    ranges: [Range<u16>; 4],
+}
+
+impl Default for PartBox {
+    /// Returns the box of every part with ratings from 1 to 4000.
+    #[inline]
+    fn default() -> Self {
+        Self::with([
+            1..MAX_RATING + 1,
+            1..MAX_RATING + 1,
+            1..MAX_RATING + 1,
+            1..MAX_RATING + 1,
+        ])
+    }
+}
+
+impl PartBox {
+    #[inline]
+    pub const fn with(ranges: [Range<u16>; 4]) -> Self {
+        Self { ranges }
+    }
+
+    #[inline]
+    pub const fn get_range(&self, category: Category) -> &Range<u16> {
+        &self.ranges[category.index()]
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(Range::is_empty)
+    }
+
+    /// Returns the number of parts in the box.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|range| u64::from(range.end.saturating_sub(range.start)))
+            .product()
+    }
+}
+
+/// Workflows with interned names, which sort parts and boxes of parts starting from `in`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkflowEngine {
+    names: Vec<String>,
+    workflow_ids: AHashMap<String, WorkflowId>,
+    workflows: Vec<Workflow>,
+}
+
+impl WorkflowEngine {
+    /// Parses workflows like `px{a<2006:qkq,m>2090:A,rfg}` from `lines` until the first blank
+    /// line.
+    pub fn parse_lines<'a>(
+        lines: &mut impl Iterator<Item = InputLine<'a>>,
+    ) -> Result<Self, ParseError> {
+        let mut engine = Self::default();
+        // Where each workflow was first mentioned, and the line that defines it.
+        let mut references = Vec::new();
+        let mut definition_line_nos = Vec::new();
+
+        for line in lines.take_while(|line| !line.as_str().is_empty()) {
+            let (name, rules) = line.split_once(line.as_str(), '{', "`{`")?;
+            let rules = rules
+                .strip_suffix('}')
+                .ok_or_else(|| line.error_at_end("`}`"))?;
+
+            let workflow_id = engine.intern(name, &line, &mut references);
+            definition_line_nos.resize(engine.names.len(), None);
+            if definition_line_nos[workflow_id as usize]
+                .replace(line.line_no())
+                .is_some()
+            {
+                return Err(line.error(name, "a workflow defined only once"));
+            }
+
+            let mut workflow = Workflow::default();
+            let mut fallback = None;
+            for rule in rules.split(',') {
+                let Some((condition, target)) = rule.split_once(':') else {
+                    if fallback.is_some() {
+                        return Err(line.error(rule, "a rule like `a<2006:qkq`"));
+                    }
+
+                    fallback = Some(engine.parse_target(rule, &line, &mut references));
+                    continue;
+                };
+
+                if fallback.is_some() {
+                    return Err(line.error(rule, "the last rule to have no condition"));
+                }
+
+                let condition = Self::parse_condition(condition, &line)?;
+                let target = engine.parse_target(target, &line, &mut references);
+                workflow.rules.push((condition, target));
+            }
+
+            workflow.fallback = fallback.ok_or_else(|| {
+                line.error(&rules[rules.len()..], "a final rule without a condition")
+            })?;
+
+            engine
+                .workflows
+                .resize_with(engine.names.len(), Workflow::default);
+            engine.workflows[workflow_id as usize] = workflow;
+        }
+
+        definition_line_nos.resize(engine.names.len(), None);
+        engine
+            .workflows
+            .resize_with(engine.names.len(), Workflow::default);
+
+        for (line_no, reference) in definition_line_nos.iter().zip(references) {
+            if line_no.is_none() {
+                return Err(reference);
+            }
+        }
+
+        match engine.get_workflow(START_WORKFLOW) {
+            Some(start) => engine.check_acyclic(start, &definition_line_nos)?,
+            None => return Err(ParseError::new(DAY, 1, 1, "a workflow named `in`")),
+        }
+
+        Ok(engine)
+    }
+
+    fn parse_condition(condition: &str, line: &InputLine) -> Result<Condition, ParseError> {
+        let category = match condition.get(..1) {
+            Some("x") => Category::X,
+            Some("m") => Category::M,
+            Some("a") => Category::A,
+            Some("s") => Category::S,
+            _ => return Err(line.error(condition, "`x`, `m`, `a` or `s`")),
+        };
+        let comparison = match condition.get(1..2) {
+            Some("<") => Comparison::Less,
+            Some(">") => Comparison::Greater,
+            _ => return Err(line.error(&condition[1..], "`<` or `>`")),
+        };
+
+        let value = &condition[2..];
+        match line.parse(value, "a rating from 0 to 4000")? {
+            value @ 0..=MAX_RATING => Ok(Condition::with(category, comparison, value)),
+            _ => Err(line.error(value, "a rating from 0 to 4000")),
+        }
+    }
+
+    fn parse_target(
+        &mut self,
+        target: &str,
+        line: &InputLine,
+        references: &mut Vec<ParseError>,
+    ) -> Target {
+        match target {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(self.intern(name, line, references)),
+        }
+    }
+
+    /// Returns the id of `name`, remembering where it was first mentioned if it is new.
+    fn intern(
+        &mut self,
+        name: &str,
+        line: &InputLine,
+        references: &mut Vec<ParseError>,
+    ) -> WorkflowId {
+        if let Some(&workflow_id) = self.workflow_ids.get(name) {
+            return workflow_id;
+        }
+
+        let workflow_id = self.names.len() as WorkflowId;
+        self.names.push(name.to_owned());
+        self.workflow_ids.insert(name.to_owned(), workflow_id);
+        references.push(line.error(name, "the name of a defined workflow"));
+
+        workflow_id
+    }
+
+    fn check_acyclic(
+        &self,
+        start: WorkflowId,
+        definition_line_nos: &[Option<usize>],
+    ) -> Result<(), ParseError> {
+        const UNVISITED: u8 = 0;
+        const VISITING: u8 = 1;
+        const VISITED: u8 = 2;
+
+        let mut states = vec![UNVISITED; self.workflows.len()];
+        let mut stack = vec![(start, 0)];
+        states[start as usize] = VISITING;
+
+        while let Some((workflow_id, rule_index)) = stack.pop() {
+            let workflow = &self.workflows[workflow_id as usize];
+            let targets = workflow.rules.iter().map(|&(_, target)| target);
+            let Some(target) = targets.chain([workflow.fallback()]).nth(rule_index) else {
+                states[workflow_id as usize] = VISITED;
+                continue;
+            };
+
+            stack.push((workflow_id, rule_index + 1));
+            if let Target::Workflow(next_id) = target {
+                match states[next_id as usize] {
+                    UNVISITED => {
+                        states[next_id as usize] = VISITING;
+                        stack.push((next_id, 0));
+                    }
+                    VISITING => {
+                        let line_no = definition_line_nos[workflow_id as usize].unwrap();
+                        let expected = "workflows that never lead back to themselves";
+                        return Err(ParseError::new(DAY, line_no, 1, expected));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_workflow(&self, name: &str) -> Option<WorkflowId> {
+        self.workflow_ids.get(name).copied()
+    }
+
+    #[inline]
+    pub fn get_name(&self, workflow_id: WorkflowId) -> &str {
+        &self.names[workflow_id as usize]
+    }
+
+    #[inline]
+    pub fn get_definition(&self, workflow_id: WorkflowId) -> &Workflow {
+        &self.workflows[workflow_id as usize]
+    }
+
+    #[inline]
+    fn get_start(&self) -> WorkflowId {
+        self.workflow_ids[START_WORKFLOW]
+    }
+
+    /// Sorts `part` through the workflows, returning whether it ends up accepted.
+    pub fn accepts(&self, part: &Part) -> bool {
+        let mut workflow_id = self.get_start();
+        loop {
+            let workflow = &self.workflows[workflow_id as usize];
+            let target = workflow
+                .rules
+                .iter()
+                .find(|(condition, _)| condition.matches(part))
+                .map_or(workflow.fallback(), |&(_, target)| target);
+
+            match target {
+                Target::Accept => break true,
+                Target::Reject => break false,
+                Target::Workflow(next_id) => workflow_id = next_id,
+            }
+        }
+    }
+
+    /// Sorts every part in `part_box` through the workflows at once, splitting the box at each
+    /// condition, and returns the disjoint boxes that end up accepted.
+    pub fn get_accepted_boxes(&self, part_box: PartBox) -> Vec<PartBox> {
+        let mut accepted_boxes = Vec::new();
+        let mut pending = vec![(self.get_start(), part_box)];
+
+        while let Some((workflow_id, mut part_box)) = pending.pop() {
+            let workflow = &self.workflows[workflow_id as usize];
+            let mut outcomes = Vec::with_capacity(workflow.rules.len() + 1);
+            for (condition, target) in &workflow.rules {
+                let (matching_box, rest_box) = condition.split(&part_box);
+                outcomes.push((*target, matching_box));
+                part_box = rest_box;
+            }
+            outcomes.push((workflow.fallback(), part_box));
+
+            for (target, part_box) in outcomes {
+                if part_box.is_empty() {
+                    continue;
+                }
+
+                match target {
+                    Target::Accept => accepted_boxes.push(part_box),
+                    Target::Reject => {}
+                    Target::Workflow(next_id) => pending.push((next_id, part_box)),
+                }
+            }
+        }
+
+        accepted_boxes
+    }
+
+    /// Counts the parts in `part_box` that end up accepted.
+    #[inline]
+    pub fn count_accepted(&self, part_box: PartBox) -> u64 {
+        self.get_accepted_boxes(part_box)
+            .iter()
+            .map(PartBox::len)
+            .sum()
+    }
+}
+
+/// The workflows together with the parts to sort through them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SortingSystem {
+    engine: WorkflowEngine,
+    parts: Vec<Part>,
+}
+
+impl SortingSystem {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut lines = input_lines(DAY, s);
+        let engine = WorkflowEngine::parse_lines(&mut lines)?;
+        let parts = lines
+            .filter(|line| !line.as_str().is_empty())
+            .map(|line| parse_part(&line))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { engine, parts })
+    }
+
+    #[inline]
+    pub const fn engine(&self) -> &WorkflowEngine {
+        &self.engine
+    }
+
+    #[inline]
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+}
+
+/// Parses a part like `{x=787,m=2655,a=1222,s=2876}`.
+fn parse_part(line: &InputLine) -> Result<Part, ParseError> {
+    let ratings = line
+        .as_str()
+        .strip_prefix('{')
+        .ok_or_else(|| line.error(line.as_str(), "`{`"))?
+        .strip_suffix('}')
+        .ok_or_else(|| line.error_at_end("`}`"))?;
+
+    let mut part = Part::default();
+    let mut ratings = ratings.split(',');
+    for (category, name) in Category::ALL.into_iter().zip(["x=", "m=", "a=", "s="]) {
+        let rating = ratings
+            .next()
+            .ok_or_else(|| line.error_at_end("four ratings"))?;
+        let value = rating
+            .strip_prefix(name)
+            .ok_or_else(|| line.error(rating, "a rating like `x=787`"))?;
+        part.ratings[category.index()] = line.parse(value, "a rating")?;
+    }
+
+    match ratings.next() {
+        Some(rating) => Err(line.error(rating, "`}`")),
+        None => Ok(part),
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day19)]
+pub fn generate_input(input: &str) -> Result<SortingSystem, ParseError> {
+    SortingSystem::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day19, part1)]
+pub fn solve_part1(input: &SortingSystem) -> u32 {
+    input
+        .parts()
+        .iter()
+        .filter(|part| input.engine().accepts(part))
+        .map(Part::get_rating_sum)
+        .sum()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day19, part2)]
+pub fn solve_part2(input: &SortingSystem) -> u64 {
+    input.engine().count_accepted(PartBox::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Part, PartBox};
+
+    const SAMPLE_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 19114)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 167409079868000)
+    }
+
+    #[test]
+    fn test_count_accepted_matches_accepts() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let engine = input.engine();
+        for part in input.parts() {
+            let ratings = part.ratings.map(|rating| rating..rating + 1);
+            let expected = u64::from(engine.accepts(part));
+            assert_eq!(engine.count_accepted(PartBox::with(ratings)), expected);
+        }
+
+        // Ranges straddling some of the thresholds, small enough to check part by part.
+        let ranges = [1400..1420, 835..845, 2000..2010, 1345..1355];
+        let expected = ranges[0]
+            .clone()
+            .flat_map(|x| ranges[1].clone().map(move |m| (x, m)))
+            .flat_map(|(x, m)| ranges[2].clone().map(move |a| (x, m, a)))
+            .flat_map(|(x, m, a)| ranges[3].clone().map(move |s| Part::with([x, m, a, s])))
+            .filter(|part| engine.accepts(part))
+            .count() as u64;
+        assert_eq!(engine.count_accepted(PartBox::with(ranges)), expected)
+    }
+
+    #[test]
+    fn test_generate_input_undefined_workflow() {
+        let error = super::generate_input("in{x<10:A,abc}\n\n{x=1,m=2,a=3,s=4}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 19, line 1, column 11: expected the name of a defined workflow"
+        )
+    }
+
+    #[test]
+    fn test_generate_input_missing_fallback() {
+        let error = super::generate_input("in{x<10:A}\n\n{x=1,m=2,a=3,s=4}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 19, line 1, column 10: expected a final rule without a condition"
+        )
+    }
+
+    #[test]
+    fn test_generate_input_cycle() {
+        let error = super::generate_input("in{x<10:A,abc}\nabc{in}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 19, line 2, column 1: expected workflows that never lead back to themselves"
+        )
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::{input_lines, InputLine, ParseError};
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct GameSet {
     red: u32,
@@ -18,7 +20,9 @@ impl GameSet {
     }
 }
 
-fn parse_set(set: &str) -> GameSet {
+const DAY: u8 = 2;
+
+fn parse_set(line: &InputLine, set: &str) -> Result<GameSet, ParseError> {
     const COLOR_SEPARATOR: char = ',';
     const COLOR_COUNT_SEPARATOR: char = ' ';
 
@@ -27,44 +31,42 @@ fn parse_set(set: &str) -> GameSet {
     let mut blue = u32::MIN;
 
     for color_count in set.split(COLOR_SEPARATOR) {
-        let mut color_count = color_count.trim().split(COLOR_COUNT_SEPARATOR);
+        let color_count = color_count.trim();
+        let (count, color) =
+            line.split_once(color_count, COLOR_COUNT_SEPARATOR, "a cube count and color")?;
 
-        let count = color_count.next().unwrap().parse::<u32>().unwrap();
-        let color = color_count.last().unwrap();
+        let count = line.parse::<u32>(count, "a cube count")?;
+        let color = color.trim();
 
         match color {
             "red" => red += count,
             "green" => green += count,
             "blue" => blue += count,
-            _ => unreachable!(),
+            _ => return Err(line.error(color, "`red`, `green` or `blue`")),
         }
     }
-    GameSet::new(red, green, blue)
+    Ok(GameSet::new(red, green, blue))
 }
 
 #[inline]
-fn parse_all_sets(line: &str) -> Vec<GameSet> {
+fn parse_all_sets(line: &InputLine) -> Result<Vec<GameSet>, ParseError> {
     const KEY_VALUE_SEPARATOR: char = ':';
     const SET_SEPARATOR: char = ';';
 
-    line.split(KEY_VALUE_SEPARATOR)
-        .last()
-        .unwrap()
-        .split(SET_SEPARATOR)
-        .map(parse_set)
+    let (_, sets) = line.split_once(line.as_str(), KEY_VALUE_SEPARATOR, "`:`")?;
+    sets.split(SET_SEPARATOR)
+        .map(|set| parse_set(line, set))
         .collect()
 }
 
 #[aoc_runner_derive::aoc_generator(day2)]
-pub fn generate_input(input: &str) -> Vec<Game> {
-    input
-        .lines()
-        .enumerate()
-        .map(|(id, line)| {
-            let id = (id + 1) as u32;
-            let sets = parse_all_sets(line);
+pub fn generate_input(input: &str) -> Result<Vec<Game>, ParseError> {
+    input_lines(DAY, input)
+        .map(|line| {
+            let id = line.line_no() as u32;
+            let sets = parse_all_sets(&line)?;
 
-            Game { id, sets }
+            Ok(Game { id, sets })
         })
         .collect()
 }
@@ -125,7 +127,8 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
-        );
+        )
+        .unwrap();
         assert_eq!(super::solve_part1(&input), 8)
     }
 
@@ -137,7 +140,27 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
-        );
+        )
+        .unwrap();
         assert_eq!(super::solve_part2(&input), 2286)
     }
+
+    #[test]
+    fn test_generate_input_errors() {
+        let error = super::generate_input("Game 1: 3 blue, 4 red\nGame 2: 1 blue; x green")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "day 2, line 2, column 17: expected a cube count");
+
+        let error = super::generate_input("Game 1: 3 blue, 4 purple")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 2, line 1, column 19: expected `red`, `green` or `blue`"
+        );
+
+        let error = super::generate_input("Game 1 3 blue").unwrap_err();
+        assert_eq!((error.line_no(), error.column()), (1, 14))
+    }
 }
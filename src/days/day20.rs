@@ -0,0 +1,561 @@
+use std::{collections::VecDeque, error, fmt};
+
+use ahash::AHashMap;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 20;
+
+pub type ModuleId = u32;
+
+const BROADCASTER: &str = "broadcaster";
+const FINAL_MACHINE: &str = "rx";
+
+const PART1_PRESSES: usize = 1000;
+const MAX_PRESSES: u64 = 1 << 20;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ModuleKind {
+    Broadcaster,
+    /// `%`: flips between off and on at each low pulse, sending high when it turns on.
+    FlipFlop,
+    /// `&`: sends low once the last pulse from every input was high, and high otherwise.
+    Conjunction,
+    /// A module that is only ever sent pulses, like `output` or `rx`.
+    #[default]
+    Untyped,
+}
+
+/// Modules with interned names, and the cables between them. There is always a broadcaster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleNetwork {
+    names: Vec<String>,
+    module_ids: AHashMap<String, ModuleId>,
+    kinds: Vec<ModuleKind>,
+    destinations: Vec<Vec<ModuleId>>,
+    inputs: Vec<Vec<ModuleId>>,
+    broadcaster: ModuleId,
+}
+
+impl Default for ModuleNetwork {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModuleNetwork {
+    /// Returns a network with only a broadcaster, which sends pulses nowhere.
+    pub fn new() -> Self {
+        let mut network = Self {
+            names: Vec::new(),
+            module_ids: AHashMap::new(),
+            kinds: Vec::new(),
+            destinations: Vec::new(),
+            inputs: Vec::new(),
+            broadcaster: ModuleId::MIN,
+        };
+        network.broadcaster = network.intern(BROADCASTER);
+        network.kinds[network.broadcaster as usize] = ModuleKind::Broadcaster;
+
+        network
+    }
+
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut network = Self::new();
+        let mut is_defined = vec![false; network.len()];
+
+        for line in input_lines(DAY, s).filter(|line| !line.as_str().is_empty()) {
+            let (module, destinations) = line.split_once(line.as_str(), '-', "`->`")?;
+            let destinations = destinations
+                .strip_prefix('>')
+                .ok_or_else(|| line.error(destinations, "`->`"))?;
+
+            let module = module.trim_end();
+            let (kind, name) = if let Some(name) = module.strip_prefix('%') {
+                (ModuleKind::FlipFlop, name)
+            } else if let Some(name) = module.strip_prefix('&') {
+                (ModuleKind::Conjunction, name)
+            } else if module == BROADCASTER {
+                (ModuleKind::Broadcaster, module)
+            } else {
+                return Err(line.error(module, "`broadcaster` or a module name after `%` or `&`"));
+            };
+            if kind != ModuleKind::Broadcaster && name == BROADCASTER {
+                return Err(line.error(module, "`broadcaster` without `%` or `&`"));
+            }
+
+            let module_id = network.intern(name);
+            is_defined.resize(network.names.len(), false);
+            if std::mem::replace(&mut is_defined[module_id as usize], true) {
+                return Err(line.error(name, "a module defined only once"));
+            }
+            network.kinds[module_id as usize] = kind;
+
+            for destination in destinations.split(',').map(str::trim) {
+                if destination.is_empty() {
+                    return Err(line.error(destination, "a module name"));
+                }
+
+                let destination_id = network.intern(destination);
+                network.destinations[module_id as usize].push(destination_id);
+                network.inputs[destination_id as usize].push(module_id);
+            }
+        }
+
+        if !is_defined[network.broadcaster as usize] {
+            let line_no = input_lines(DAY, s).count().max(1);
+            return Err(ParseError::new(DAY, line_no, 1, "a `broadcaster` module"));
+        }
+
+        Ok(network)
+    }
+
+    /// Returns the id of `name`, adding an untyped module if it is new.
+    fn intern(&mut self, name: &str) -> ModuleId {
+        if let Some(&module_id) = self.module_ids.get(name) {
+            return module_id;
+        }
+
+        let module_id = self.names.len() as ModuleId;
+        self.names.push(name.to_owned());
+        self.module_ids.insert(name.to_owned(), module_id);
+        self.kinds.push(ModuleKind::Untyped);
+        self.destinations.push(Vec::new());
+        self.inputs.push(Vec::new());
+
+        module_id
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    #[inline]
+    pub const fn broadcaster(&self) -> ModuleId {
+        self.broadcaster
+    }
+
+    #[inline]
+    pub fn get_module(&self, name: &str) -> Option<ModuleId> {
+        self.module_ids.get(name).copied()
+    }
+
+    #[inline]
+    pub fn get_name(&self, module_id: ModuleId) -> &str {
+        &self.names[module_id as usize]
+    }
+
+    #[inline]
+    pub fn get_kind(&self, module_id: ModuleId) -> ModuleKind {
+        self.kinds[module_id as usize]
+    }
+
+    #[inline]
+    pub fn get_destinations(&self, module_id: ModuleId) -> &[ModuleId] {
+        &self.destinations[module_id as usize]
+    }
+
+    #[inline]
+    pub fn get_inputs(&self, module_id: ModuleId) -> &[ModuleId] {
+        &self.inputs[module_id as usize]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Pulse {
+    /// The module that sent the pulse, or `None` for the button.
+    from: Option<ModuleId>,
+    to: ModuleId,
+    is_high: bool,
+}
+
+impl Pulse {
+    #[inline]
+    pub const fn from(&self) -> Option<ModuleId> {
+        self.from
+    }
+
+    #[inline]
+    pub const fn to(&self) -> ModuleId {
+        self.to
+    }
+
+    #[inline]
+    pub const fn is_high(&self) -> bool {
+        self.is_high
+    }
+}
+
+/// Every pulse sent after one press of the button, in the order they were processed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PressLog<'a> {
+    network: &'a ModuleNetwork,
+    pulses: Vec<Pulse>,
+}
+
+impl PressLog<'_> {
+    #[inline]
+    pub fn pulses(&self) -> &[Pulse] {
+        &self.pulses
+    }
+
+    #[inline]
+    pub fn count_high(&self) -> usize {
+        self.pulses.iter().filter(|pulse| pulse.is_high).count()
+    }
+
+    #[inline]
+    pub fn count_low(&self) -> usize {
+        self.pulses.len() - self.count_high()
+    }
+}
+
+/// Lists the pulses one per line, e.g. `broadcaster -low-> a`.
+impl fmt::Display for PressLog<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, pulse) in self.pulses.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            let from = pulse
+                .from
+                .map_or("button", |from| self.network.get_name(from));
+            let level = if pulse.is_high { "high" } else { "low" };
+            write!(f, "{from} -{level}-> {}", self.network.get_name(pulse.to))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of every flip-flop and conjunction in a network between button presses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulator<'a> {
+    network: &'a ModuleNetwork,
+    is_on: Vec<bool>,
+    /// The last pulse each conjunction got from each of its inputs, in the order of
+    /// [`ModuleNetwork::get_inputs`].
+    last_pulses: Vec<Vec<bool>>,
+    high_input_counts: Vec<usize>,
+    presses: u64,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(network: &'a ModuleNetwork) -> Self {
+        Self {
+            network,
+            is_on: vec![false; network.len()],
+            last_pulses: network
+                .inputs
+                .iter()
+                .map(|inputs| vec![false; inputs.len()])
+                .collect(),
+            high_input_counts: vec![0; network.len()],
+            presses: u64::MIN,
+        }
+    }
+
+    /// Returns how many times the button has been pressed.
+    #[inline]
+    pub const fn presses(&self) -> u64 {
+        self.presses
+    }
+
+    /// Presses the button once and processes pulses until none are left.
+    pub fn press_button(&mut self) -> PressLog<'a> {
+        let network = self.network;
+        let mut pulses = Vec::new();
+        let mut queue = VecDeque::from([Pulse {
+            from: None,
+            to: network.broadcaster,
+            is_high: false,
+        }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            pulses.push(pulse);
+
+            let module_id = pulse.to as usize;
+            let is_high = match network.kinds[module_id] {
+                ModuleKind::Broadcaster => pulse.is_high,
+                ModuleKind::FlipFlop if pulse.is_high => continue,
+                ModuleKind::FlipFlop => {
+                    self.is_on[module_id] = !self.is_on[module_id];
+                    self.is_on[module_id]
+                }
+                ModuleKind::Conjunction => {
+                    // Only the broadcaster gets pulses from the button, so this one came from a
+                    // module.
+                    let from = pulse.from.unwrap();
+                    // A module may send to the same conjunction more than once.
+                    for (input, last_pulse) in network.inputs[module_id]
+                        .iter()
+                        .zip(&mut self.last_pulses[module_id])
+                    {
+                        if *input == from && *last_pulse != pulse.is_high {
+                            *last_pulse = pulse.is_high;
+                            if pulse.is_high {
+                                self.high_input_counts[module_id] += 1;
+                            } else {
+                                self.high_input_counts[module_id] -= 1;
+                            }
+                        }
+                    }
+
+                    self.high_input_counts[module_id] != network.inputs[module_id].len()
+                }
+                ModuleKind::Untyped => continue,
+            };
+
+            for &to in &network.destinations[module_id] {
+                queue.push_back(Pulse {
+                    from: Some(pulse.to),
+                    to,
+                    is_high,
+                });
+            }
+        }
+
+        self.presses += 1;
+        PressLog { network, pulses }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MachineStartError {
+    NoFinalMachine,
+    /// `rx` is not fed by exactly one conjunction.
+    UnexpectedFeeder,
+    /// An input of the conjunction feeding `rx` does not send it high pulses every so many
+    /// presses, starting from the first.
+    NoCycle {
+        input: String,
+    },
+}
+
+impl fmt::Display for MachineStartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoFinalMachine => write!(f, "no module sends pulses to `{FINAL_MACHINE}`"),
+            Self::UnexpectedFeeder => {
+                write!(f, "`{FINAL_MACHINE}` is not fed by a single conjunction")
+            }
+            Self::NoCycle { input } => write!(
+                f,
+                "{input} does not send high pulses in a cycle aligned with the first press"
+            ),
+        }
+    }
+}
+
+impl error::Error for MachineStartError {}
+
+#[inline]
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day20)]
+pub fn generate_input(input: &str) -> Result<ModuleNetwork, ParseError> {
+    ModuleNetwork::parse_str(input)
+}
+
+#[aoc_runner_derive::aoc(day20, part1)]
+pub fn solve_part1(input: &ModuleNetwork) -> usize {
+    let mut simulator = Simulator::new(input);
+    let (mut low_pulses, mut high_pulses) = (usize::MIN, usize::MIN);
+    for _ in 0..PART1_PRESSES {
+        let log = simulator.press_button();
+        low_pulses += log.count_low();
+        high_pulses += log.count_high();
+    }
+
+    low_pulses * high_pulses
+}
+
+/// Finds the fewest presses that send a single low pulse to `rx`.
+///
+/// `rx` is fed by one conjunction, which sends it low once all of its own inputs have sent it
+/// high during the same press. Each of those inputs does so every so many presses, so the
+/// answer is the least common multiple of their cycle lengths.
+#[aoc_runner_derive::aoc(day20, part2)]
+pub fn solve_part2(input: &ModuleNetwork) -> Result<u64, MachineStartError> {
+    let final_machine = input
+        .get_module(FINAL_MACHINE)
+        .ok_or(MachineStartError::NoFinalMachine)?;
+    let &[feeder] = input.get_inputs(final_machine) else {
+        return Err(MachineStartError::UnexpectedFeeder);
+    };
+    if input.get_kind(feeder) != ModuleKind::Conjunction {
+        return Err(MachineStartError::UnexpectedFeeder);
+    }
+
+    let feeder_inputs = input.get_inputs(feeder);
+    // The presses during which each input first and then next sent the feeder a high pulse.
+    let mut high_presses = vec![Vec::with_capacity(2); feeder_inputs.len()];
+
+    let mut simulator = Simulator::new(input);
+    while simulator.presses() < MAX_PRESSES && high_presses.iter().any(|presses| presses.len() < 2)
+    {
+        let log = simulator.press_button();
+        for pulse in log.pulses() {
+            if pulse.to != feeder || !pulse.is_high {
+                continue;
+            }
+
+            let i = feeder_inputs
+                .iter()
+                .position(|&input| Some(input) == pulse.from)
+                .unwrap();
+            if high_presses[i].last() != Some(&simulator.presses()) && high_presses[i].len() < 2 {
+                high_presses[i].push(simulator.presses());
+            }
+        }
+    }
+
+    let mut presses = 1;
+    for (&feeder_input, high_presses) in feeder_inputs.iter().zip(high_presses) {
+        match high_presses[..] {
+            [first, second] if second == 2 * first => {
+                presses = presses / gcd(presses, first) * first;
+            }
+            _ => {
+                let input = input.get_name(feeder_input).to_owned();
+                return Err(MachineStartError::NoCycle { input });
+            }
+        }
+    }
+
+    Ok(presses)
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT_1: &str = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    const SAMPLE_INPUT_2: &str = "broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+    #[test]
+    fn test_solve_part1_sample_1() {
+        let input = super::generate_input(SAMPLE_INPUT_1).unwrap();
+        assert_eq!(super::solve_part1(&input), 32000000)
+    }
+
+    #[test]
+    fn test_solve_part1_sample_2() {
+        let input = super::generate_input(SAMPLE_INPUT_2).unwrap();
+        assert_eq!(super::solve_part1(&input), 11687500)
+    }
+
+    #[test]
+    fn test_solve_part2_counters() {
+        // Two counters built like the real inputs: `ca` sends `con` a high pulse every 3
+        // presses and `cb` every 5, resetting its flip-flops each time.
+        let input = super::generate_input(
+            "broadcaster -> a0, b0
+%a0 -> a1, ca
+%a1 -> ca
+&ca -> a0, sa
+&sa -> con
+%b0 -> b1, cb
+%b1 -> b2
+%b2 -> cb
+&cb -> b1, b0, sb
+&sb -> con
+&con -> rx",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), Ok(15));
+
+        let rx = input.get_module("rx").unwrap();
+        let mut simulator = super::Simulator::new(&input);
+        while !simulator
+            .press_button()
+            .pulses()
+            .iter()
+            .any(|pulse| pulse.to() == rx && !pulse.is_high())
+        {}
+        assert_eq!(simulator.presses(), 15)
+    }
+
+    #[test]
+    fn test_solve_part2_no_final_machine() {
+        let input = super::generate_input(SAMPLE_INPUT_1).unwrap();
+        assert_eq!(
+            super::solve_part2(&input),
+            Err(super::MachineStartError::NoFinalMachine)
+        )
+    }
+
+    #[test]
+    fn test_generate_input_typed_broadcaster() {
+        let error = super::generate_input("&broadcaster -> a\n%a -> broadcaster").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 20, line 1, column 1: expected `broadcaster` without `%` or `&`"
+        );
+
+        let error = super::generate_input("%a -> broadcaster").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 20, line 1, column 1: expected a `broadcaster` module"
+        )
+    }
+
+    #[test]
+    fn test_press_button_default_network() {
+        let input = super::ModuleNetwork::default();
+        let mut simulator = super::Simulator::new(&input);
+        assert_eq!(
+            simulator.press_button().to_string(),
+            "button -low-> broadcaster"
+        )
+    }
+
+    #[test]
+    fn test_press_button_sample() {
+        let input = super::generate_input(SAMPLE_INPUT_2).unwrap();
+        let mut simulator = super::Simulator::new(&input);
+        assert_eq!(
+            simulator.press_button().to_string(),
+            "button -low-> broadcaster
+broadcaster -low-> a
+a -high-> inv
+a -high-> con
+inv -low-> b
+con -high-> output
+b -high-> con
+con -low-> output"
+        );
+        assert_eq!(
+            simulator.press_button().to_string(),
+            "button -low-> broadcaster
+broadcaster -low-> a
+a -low-> inv
+a -low-> con
+inv -high-> b
+con -high-> output"
+        );
+        assert_eq!(simulator.presses(), 2)
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+
+use ahash::AHashSet;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 21;
+
+pub type Coords = (usize, usize);
+
+const GARDEN_PLOT: u8 = b'.';
+const ROCK: u8 = b'#';
+const START: u8 = b'S';
+
+const PART1_STEPS: usize = 64;
+const PART2_STEPS: usize = 26_501_365;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Garden {
+    is_rock: Vec<bool>,
+    width: usize,
+    height: usize,
+    start: Coords,
+}
+
+impl Garden {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut is_rock = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+        let mut start = None;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            for (col, &tile) in row.iter().enumerate() {
+                match tile {
+                    ROCK => is_rock.push(true),
+                    GARDEN_PLOT => is_rock.push(false),
+                    START if start.is_none() => {
+                        start = Some((height, col));
+                        is_rock.push(false);
+                    }
+                    START => return Err(line.error(&line.as_str()[col..], "a single start tile")),
+                    _ => return Err(line.error(&line.as_str()[col..], "`.`, `#` or `S`")),
+                }
+            }
+
+            height += 1;
+        }
+
+        let start = start.ok_or(ParseError::new(DAY, height.max(1), 1, "a start tile"))?;
+        Ok(Self {
+            is_rock,
+            width,
+            height,
+            start,
+        })
+    }
+
+    #[inline]
+    pub const fn start(&self) -> Coords {
+        self.start
+    }
+
+    /// Counts the garden plots the elf can end on after exactly `steps` steps, staying inside
+    /// the garden.
+    pub fn count_reachable(&self, steps: usize) -> usize {
+        let mut distances = vec![usize::MAX; self.is_rock.len()];
+        let mut queue = VecDeque::from([(self.start, 0)]);
+        distances[self.start.0 * self.width + self.start.1] = 0;
+
+        let mut reachable = usize::MIN;
+        while let Some(((row, col), distance)) = queue.pop_front() {
+            if distance % 2 == steps % 2 {
+                reachable += 1;
+            }
+            if distance == steps {
+                continue;
+            }
+
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row, col + 1),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+            ];
+            for (next_row, next_col) in neighbours {
+                if next_row >= self.height || next_col >= self.width {
+                    continue;
+                }
+
+                let index = next_row * self.width + next_col;
+                if !self.is_rock[index] && distances[index] == usize::MAX {
+                    distances[index] = distance + 1;
+                    queue.push_back(((next_row, next_col), distance + 1));
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Counts the garden plots the elf can end on after exactly `steps` steps, in the garden
+    /// repeated infinitely in every direction.
+    ///
+    /// Runs a breadth-first search over every plot within `steps` of the start, so this is
+    /// only meant for small `steps`.
+    #[inline]
+    pub fn count_reachable_infinite(&self, steps: usize) -> usize {
+        self.get_reachable_counts_infinite(steps)[steps]
+    }
+
+    /// Returns the number of plots reachable in the infinite garden after exactly each number
+    /// of steps up to `max_steps`, from a single breadth-first search.
+    fn get_reachable_counts_infinite(&self, max_steps: usize) -> Vec<usize> {
+        let start = (self.start.0 as i64, self.start.1 as i64);
+        let mut visited = AHashSet::from_iter([start]);
+        let mut frontier = vec![start];
+
+        // A plot first reached after `d` steps can be ended on after `d + 2`, `d + 4`, ...
+        let mut reachable_counts = vec![1];
+        for distance in 1..=max_steps {
+            let mut next_frontier = Vec::new();
+            for (row, col) in frontier {
+                for next in [
+                    (row - 1, col),
+                    (row, col + 1),
+                    (row + 1, col),
+                    (row, col - 1),
+                ] {
+                    if !self.is_rock_at(next) && visited.insert(next) {
+                        next_frontier.push(next);
+                    }
+                }
+            }
+
+            let same_parity_count = distance
+                .checked_sub(2)
+                .map_or(0, |distance| reachable_counts[distance]);
+            reachable_counts.push(same_parity_count + next_frontier.len());
+            frontier = next_frontier;
+        }
+
+        reachable_counts
+    }
+
+    /// Counts the garden plots reachable after exactly `steps` steps in the infinite garden,
+    /// for any `steps`.
+    ///
+    /// Samples the counts every garden width steps, from `steps` modulo the width. Once those
+    /// samples grow quadratically, i.e. their second differences stop changing, the rest
+    /// follow from the last of them. Returns `None` if the garden is not square or the
+    /// samples never settle.
+    pub fn extrapolate_reachable_infinite(&self, steps: usize) -> Option<u64> {
+        const MAX_PERIODS: usize = 64;
+        const SETTLED_DIFFERENCES: usize = 3;
+
+        let period = self.width;
+        if period == 0 || self.height != period {
+            return None;
+        }
+
+        let (remainder, target) = (steps % period, steps / period);
+        let mut periods = 4;
+        while periods <= MAX_PERIODS {
+            let max_steps = remainder + periods.min(target) * period;
+            let reachable_counts = self.get_reachable_counts_infinite(max_steps);
+            if target <= periods {
+                return Some(reachable_counts[steps] as u64);
+            }
+
+            let samples = (0..=periods)
+                .map(|k| reachable_counts[remainder + k * period] as i128)
+                .collect::<Vec<_>>();
+            let second_differences = samples
+                .windows(3)
+                .map(|window| window[2] - 2 * window[1] + window[0])
+                .collect::<Vec<_>>();
+
+            if let Some(j) = second_differences
+                .windows(SETTLED_DIFFERENCES)
+                .position(|window| window.iter().all(|&difference| difference == window[0]))
+            {
+                let t = (target - j) as i128;
+                let first_difference = samples[j + 1] - samples[j];
+                let reachable =
+                    samples[j] + t * first_difference + t * (t - 1) / 2 * second_differences[j];
+                return u64::try_from(reachable).ok();
+            }
+
+            periods *= 2;
+        }
+
+        None
+    }
+
+    #[inline]
+    fn is_rock_at(&self, (row, col): (i64, i64)) -> bool {
+        let row = row.rem_euclid(self.height as i64) as usize;
+        let col = col.rem_euclid(self.width as i64) as usize;
+        self.is_rock[row * self.width + col]
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day21)]
+pub fn generate_input(input: &str) -> Result<Garden, ParseError> {
+    Garden::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day21, part1)]
+pub fn solve_part1(input: &Garden) -> usize {
+    input.count_reachable(PART1_STEPS)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day21, part2)]
+pub fn solve_part2(input: &Garden) -> Option<u64> {
+    input.extrapolate_reachable_infinite(PART2_STEPS)
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(input.count_reachable(6), 16);
+        assert_eq!(super::solve_part1(&input), 42)
+    }
+
+    #[test]
+    fn test_count_reachable_infinite_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(input.count_reachable_infinite(6), 16);
+        assert_eq!(input.count_reachable_infinite(10), 50);
+        assert_eq!(input.count_reachable_infinite(50), 1594);
+        assert_eq!(input.count_reachable_infinite(100), 6536);
+        assert_eq!(input.count_reachable_infinite(500), 167004)
+    }
+
+    #[test]
+    fn test_extrapolate_reachable_infinite_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        for steps in [6, 10, 50, 100, 500] {
+            let expected = input.count_reachable_infinite(steps) as u64;
+            assert_eq!(input.extrapolate_reachable_infinite(steps), Some(expected));
+        }
+
+        assert_eq!(input.extrapolate_reachable_infinite(1000), Some(668697));
+        assert_eq!(input.extrapolate_reachable_infinite(5000), Some(16733044))
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 22;
+
+pub type Point = [u32; 3];
+
+const X: usize = 0;
+const Y: usize = 1;
+const Z: usize = 2;
+
+/// A brick of cubes between two corners, both included, with `start` no greater than `end` on
+/// any axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Brick {
+    start: Point,
+    end: Point,
+}
+
+impl Brick {
+    /// Returns the brick between the two corners, in either order.
+    #[inline]
+    pub fn with(corner1: Point, corner2: Point) -> Self {
+        Self {
+            start: [0, 1, 2].map(|axis| corner1[axis].min(corner2[axis])),
+            end: [0, 1, 2].map(|axis| corner1[axis].max(corner2[axis])),
+        }
+    }
+
+    /// Parses a brick like `1,0,1~1,2,1`.
+    pub fn parse(line: &InputLine) -> Result<Self, ParseError> {
+        let (corner1, corner2) = line.split_once(line.as_str(), '~', "`~`")?;
+        let corner1 = parse_point(line, corner1)?;
+        let corner2 = parse_point(line, corner2)?;
+        if corner1[Z].min(corner2[Z]) == 0 {
+            return Err(line.error(line.as_str(), "a brick above the ground, at z = 1 or more"));
+        }
+
+        Ok(Self::with(corner1, corner2))
+    }
+
+    #[inline]
+    pub const fn start(&self) -> Point {
+        self.start
+    }
+
+    #[inline]
+    pub const fn end(&self) -> Point {
+        self.end
+    }
+
+    /// Returns the cells of the brick seen from above.
+    fn get_footprint(&self) -> impl Iterator<Item = (usize, usize)> + Clone {
+        let (start, end) = (self.start, self.end);
+        (start[X]..=end[X])
+            .flat_map(move |x| (start[Y]..=end[Y]).map(move |y| (x as usize, y as usize)))
+    }
+}
+
+fn parse_point(line: &InputLine, s: &str) -> Result<Point, ParseError> {
+    let mut coords = s.split(',');
+    let mut point = Point::default();
+    for coord in &mut point {
+        let value = coords
+            .next()
+            .ok_or_else(|| line.error(&s[s.len()..], "three coordinates"))?;
+        *coord = line.parse(value, "a coordinate")?;
+    }
+
+    match coords.next() {
+        Some(coord) => Err(line.error(coord, "`~` or the end of the line")),
+        None => Ok(point),
+    }
+}
+
+/// Bricks after falling as far as they go, with which bricks rest on which.
+///
+/// Bricks are indexed in the order they settled, from the lowest up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SettledBricks {
+    bricks: Vec<Brick>,
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl SettledBricks {
+    /// Lets `bricks` fall, lowest first, tracking the top of the stack over each cell.
+    ///
+    /// Fails with the index in `bricks` of a brick that takes up some of the same cubes as a
+    /// brick below it.
+    pub fn settle(bricks: &[Brick]) -> Result<Self, usize> {
+        let mut order = (0..bricks.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&i| bricks[i].start[Z]);
+        let mut bricks = order.iter().map(|&i| bricks[i]).collect::<Vec<_>>();
+
+        let width = bricks.iter().map(|brick| brick.end[X] as usize + 1).max();
+        let depth = bricks.iter().map(|brick| brick.end[Y] as usize + 1).max();
+        let (width, depth) = (width.unwrap_or_default(), depth.unwrap_or_default());
+
+        // The height of the top of the stack and the brick there, over each cell.
+        let mut height_map: Vec<(u32, Option<usize>)> = vec![(u32::MIN, None); width * depth];
+        let mut supports = vec![Vec::new(); bricks.len()];
+        let mut supported_by = vec![Vec::new(); bricks.len()];
+
+        for (i, brick) in bricks.iter_mut().enumerate() {
+            let footprint = brick.get_footprint().map(|(x, y)| x * depth + y);
+            let top = footprint
+                .clone()
+                .map(|cell| height_map[cell].0)
+                .max()
+                .unwrap_or_default();
+            if top >= brick.start[Z] {
+                return Err(order[i]);
+            }
+
+            let below_bricks = footprint.clone().filter_map(|cell| match height_map[cell] {
+                (height, below) if height == top => below,
+                _ => None,
+            });
+            for below in below_bricks {
+                if !supported_by[i].contains(&below) {
+                    supported_by[i].push(below);
+                    supports[below].push(i);
+                }
+            }
+
+            let fall = brick.start[Z] - (top + 1);
+            brick.start[Z] -= fall;
+            brick.end[Z] -= fall;
+
+            for cell in footprint {
+                height_map[cell] = (brick.end[Z], Some(i));
+            }
+        }
+
+        Ok(Self {
+            bricks,
+            supports,
+            supported_by,
+        })
+    }
+
+    #[inline]
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    /// Returns the bricks resting directly on the brick at `index`.
+    #[inline]
+    pub fn get_supports(&self, index: usize) -> &[usize] {
+        &self.supports[index]
+    }
+
+    /// Returns the bricks the brick at `index` rests directly on.
+    #[inline]
+    pub fn get_supported_by(&self, index: usize) -> &[usize] {
+        &self.supported_by[index]
+    }
+
+    /// Returns whether removing the brick at `index` leaves every other brick in place.
+    #[inline]
+    pub fn can_disintegrate(&self, index: usize) -> bool {
+        self.supports[index]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Counts the other bricks that fall once the brick at `index` is removed.
+    pub fn count_falling(&self, index: usize) -> usize {
+        let mut is_falling = vec![false; self.bricks.len()];
+        is_falling[index] = true;
+
+        // Bricks only rest on bricks that settled before them, so one pass upwards suffices.
+        let mut falling_count = usize::MIN;
+        for above in index + 1..self.bricks.len() {
+            let supported_by = &self.supported_by[above];
+            if !supported_by.is_empty() && supported_by.iter().all(|&below| is_falling[below]) {
+                is_falling[above] = true;
+                falling_count += 1;
+            }
+        }
+
+        falling_count
+    }
+}
+
+#[aoc_runner_derive::aoc_generator(day22)]
+pub fn generate_input(input: &str) -> Result<SettledBricks, ParseError> {
+    let lines = input_lines(DAY, input).collect::<Vec<_>>();
+    let bricks = lines
+        .iter()
+        .map(Brick::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    SettledBricks::settle(&bricks).map_err(|i| {
+        let expected = "a brick that does not overlap the bricks below it";
+        lines[i].error(lines[i].as_str(), expected)
+    })
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day22, part1)]
+pub fn solve_part1(input: &SettledBricks) -> usize {
+    (0..input.bricks().len())
+        .filter(|&index| input.can_disintegrate(index))
+        .count()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day22, part2)]
+pub fn solve_part2(input: &SettledBricks) -> usize {
+    (0..input.bricks().len())
+        .map(|index| input.count_falling(index))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), 5)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), 7)
+    }
+
+    #[test]
+    fn test_settle_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let bottoms = input
+            .bricks()
+            .iter()
+            .map(|brick| brick.start()[2])
+            .collect::<Vec<_>>();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(input.get_supports(0), [1, 2]);
+        assert_eq!(input.get_supported_by(3), [1, 2]);
+        assert_eq!(input.get_supported_by(6), [5]);
+        assert_eq!(input.count_falling(0), 6);
+        assert_eq!(input.count_falling(5), 1)
+    }
+
+    #[test]
+    fn test_generate_input_overlap() {
+        let error = super::generate_input("0,0,5~0,0,5\n0,0,1~0,0,9").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 22, line 1, column 1: expected a brick that does not overlap the bricks below it"
+        )
+    }
+}
@@ -0,0 +1,301 @@
+use ahash::AHashMap;
+
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 23;
+
+pub type Coords = (usize, usize);
+
+const PATH: u8 = b'.';
+const FOREST: u8 = b'#';
+const SLOPES: [u8; 4] = [b'^', b'>', b'v', b'<'];
+
+/// The most junctions [`JunctionGraph::find_longest_path`] can track in its bitmask.
+const MAX_JUNCTIONS: usize = u64::BITS as usize;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrailMap {
+    tiles: Vec<u8>,
+    width: usize,
+    height: usize,
+    start: Coords,
+    end: Coords,
+}
+
+impl TrailMap {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut tiles = Vec::new();
+        let mut width = usize::MIN;
+        let mut height = usize::MIN;
+        let mut last_line = None;
+
+        for line in input_lines(DAY, s) {
+            let row = line.as_str().as_bytes();
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error_at_end("a row as wide as the first one"));
+            }
+
+            if let Some(col) = row
+                .iter()
+                .position(|tile| *tile != PATH && *tile != FOREST && !SLOPES.contains(tile))
+            {
+                return Err(line.error(&line.as_str()[col..], "`.`, `#`, `^`, `>`, `v` or `<`"));
+            }
+
+            if height == 0 && row.iter().filter(|&&tile| tile == PATH).count() != 1 {
+                return Err(line.error(line.as_str(), "a single path tile in the top row"));
+            }
+
+            tiles.extend_from_slice(row);
+            height += 1;
+            last_line = Some(line);
+        }
+
+        let last_line = last_line.ok_or(ParseError::new(DAY, 1, 1, "a trail map"))?;
+        let bottom_row = &tiles[(height - 1) * width..];
+        if height < 2 || bottom_row.iter().filter(|&&tile| tile == PATH).count() != 1 {
+            let expected = "a single path tile in the bottom row";
+            return Err(last_line.error(last_line.as_str(), expected));
+        }
+
+        let start_col = tiles[..width]
+            .iter()
+            .position(|&tile| tile == PATH)
+            .unwrap();
+        let end_col = bottom_row.iter().position(|&tile| tile == PATH).unwrap();
+        Ok(Self {
+            tiles,
+            width,
+            height,
+            start: (0, start_col),
+            end: (height - 1, end_col),
+        })
+    }
+
+    #[inline]
+    fn get_tile(&self, (row, col): Coords) -> u8 {
+        self.tiles[row * self.width + col]
+    }
+
+    /// Returns the tiles one step away from `coords` that are not forest, together with the
+    /// slope that allows stepping there downhill.
+    fn get_neighbours(&self, (row, col): Coords) -> impl Iterator<Item = (Coords, u8)> + '_ {
+        [
+            (row.wrapping_sub(1), col),
+            (row, col + 1),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+        ]
+        .into_iter()
+        .zip(SLOPES)
+        .filter(|&((row, col), _)| row < self.height && col < self.width)
+        .filter(|&(coords, _)| self.get_tile(coords) != FOREST)
+    }
+
+    fn is_junction(&self, coords: Coords) -> bool {
+        coords == self.start || coords == self.end || self.get_neighbours(coords).count() > 2
+    }
+
+    /// Returns whether stepping from `from` to `to` goes with the slopes, that is, neither tile
+    /// is a slope pointing any other way than `slope`.
+    fn is_downhill(&self, from: Coords, to: Coords, slope: u8) -> bool {
+        [from, to].into_iter().all(|coords| {
+            let tile = self.get_tile(coords);
+            tile == PATH || tile == slope
+        })
+    }
+
+    /// Compresses the trails into a graph of the start, the end, and every tile where they fork,
+    /// joined by the length of the corridors between them.
+    pub fn compress(&self, is_slippery: bool) -> JunctionGraph {
+        let mut junctions = vec![self.start, self.end];
+        junctions.extend(
+            (0..self.tiles.len())
+                .map(|index| (index / self.width, index % self.width))
+                .filter(|&coords| self.get_tile(coords) != FOREST)
+                .filter(|&coords| coords != self.start && coords != self.end)
+                .filter(|&coords| self.is_junction(coords)),
+        );
+        let junction_ids = junctions
+            .iter()
+            .enumerate()
+            .map(|(id, &coords)| (coords, id))
+            .collect::<AHashMap<_, _>>();
+
+        let mut edges = vec![Vec::new(); junctions.len()];
+        for (id, &junction) in junctions.iter().enumerate() {
+            for (next, slope) in self.get_neighbours(junction) {
+                let mut is_passable = !is_slippery || self.is_downhill(junction, next, slope);
+                let (mut prev, mut curr) = (junction, next);
+                let mut length = 1;
+
+                while is_passable && !self.is_junction(curr) {
+                    let Some((next, slope)) = self
+                        .get_neighbours(curr)
+                        .find(|&(coords, _)| coords != prev)
+                    else {
+                        is_passable = false;
+                        break;
+                    };
+
+                    is_passable = !is_slippery || self.is_downhill(curr, next, slope);
+                    (prev, curr) = (curr, next);
+                    length += 1;
+                }
+
+                if is_passable {
+                    edges[id].push((junction_ids[&curr], length));
+                }
+            }
+        }
+
+        JunctionGraph { junctions, edges }
+    }
+}
+
+/// Junctions of a trail map and the corridors leading from each to the next, with the start at
+/// index 0 and the end at index 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JunctionGraph {
+    junctions: Vec<Coords>,
+    edges: Vec<Vec<(usize, u32)>>,
+}
+
+impl JunctionGraph {
+    const START: usize = 0;
+    const END: usize = 1;
+
+    #[inline]
+    pub fn junctions(&self) -> &[Coords] {
+        &self.junctions
+    }
+
+    /// Returns the junctions reachable from the junction at `index`, with the length of the
+    /// corridor to each.
+    #[inline]
+    pub fn get_edges(&self, index: usize) -> &[(usize, u32)] {
+        &self.edges[index]
+    }
+
+    /// Returns the length of the longest path from the start to the end that visits no junction
+    /// twice, or `None` if there is no such path or there are too many junctions.
+    pub fn find_longest_path(&self) -> Option<u32> {
+        if self.junctions.len() > MAX_JUNCTIONS {
+            return None;
+        }
+
+        // Once next to the end, any other way would cut it off, so it must be taken.
+        let mut last_junctions = (0..self.junctions.len())
+            .filter(|&index| self.edges[index].iter().any(|&(next, _)| next == Self::END));
+        let last_junction = match (last_junctions.next(), last_junctions.next()) {
+            (Some(last_junction), None) => Some(last_junction),
+            _ => None,
+        };
+
+        self.find_longest_path_from(Self::START, 1 << Self::START, last_junction)
+    }
+
+    fn find_longest_path_from(
+        &self,
+        index: usize,
+        visited: u64,
+        last_junction: Option<usize>,
+    ) -> Option<u32> {
+        if index == Self::END {
+            return Some(0);
+        }
+
+        let edges = &self.edges[index];
+        let edges = match edges.iter().find(|&&(next, _)| next == Self::END) {
+            Some(edge) if last_junction == Some(index) => std::slice::from_ref(edge),
+            _ => edges,
+        };
+
+        edges
+            .iter()
+            .filter(|&&(next, _)| visited & (1 << next) == 0)
+            .filter_map(|&(next, length)| {
+                let rest =
+                    self.find_longest_path_from(next, visited | (1 << next), last_junction)?;
+                Some(length + rest)
+            })
+            .max()
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day23)]
+pub fn generate_input(input: &str) -> Result<TrailMap, ParseError> {
+    TrailMap::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day23, part1)]
+pub fn solve_part1(input: &TrailMap) -> Option<u32> {
+    input.compress(true).find_longest_path()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day23, part2)]
+pub fn solve_part2(input: &TrailMap) -> Option<u32> {
+    input.compress(false).find_longest_path()
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE_INPUT: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part1(&input), Some(94))
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(super::solve_part2(&input), Some(154))
+    }
+
+    #[test]
+    fn test_compress_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let graph = input.compress(false);
+        assert_eq!(graph.junctions().len(), 9);
+        assert_eq!(graph.junctions()[..2], [(0, 1), (22, 21)]);
+        assert_eq!(graph.get_edges(0), [(3, 15)]);
+        assert_eq!(graph.junctions()[3], (5, 3));
+
+        let slippery_graph = input.compress(true);
+        let edge_count = |graph: &super::JunctionGraph| {
+            (0..graph.junctions().len())
+                .map(|index| graph.get_edges(index).len())
+                .sum::<usize>()
+        };
+        assert_eq!(edge_count(&graph), 2 * edge_count(&slippery_graph))
+    }
+}
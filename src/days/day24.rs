@@ -0,0 +1,361 @@
+use std::ops::RangeInclusive;
+
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 24;
+
+pub type Vector = [i64; 3];
+
+const X: usize = 0;
+const Y: usize = 1;
+const Z: usize = 2;
+
+const PART1_TEST_AREA: RangeInclusive<i64> = 200_000_000_000_000..=400_000_000_000_000;
+
+/// A hailstone, or the rock, at `position` at time 0 and moving by `velocity` each nanosecond.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Hailstone {
+    position: Vector,
+    velocity: Vector,
+}
+
+impl Hailstone {
+    #[inline]
+    pub const fn with(position: Vector, velocity: Vector) -> Self {
+        Self { position, velocity }
+    }
+
+    /// Parses a hailstone like `19, 13, 30 @ -2,  1, -2`.
+    pub fn parse(line: &InputLine) -> Result<Self, ParseError> {
+        let (position, velocity) = line.split_once(line.as_str(), '@', "`@`")?;
+        Ok(Self {
+            position: parse_vector(line, position)?,
+            velocity: parse_vector(line, velocity)?,
+        })
+    }
+
+    #[inline]
+    pub const fn position(&self) -> Vector {
+        self.position
+    }
+
+    #[inline]
+    pub const fn velocity(&self) -> Vector {
+        self.velocity
+    }
+
+    /// Returns whether the paths of both hailstones, ignoring the Z axis, cross inside
+    /// `test_area` on both axes at a time neither hailstone has already passed.
+    pub fn crosses_xy_within(&self, other: &Self, test_area: &RangeInclusive<i64>) -> bool {
+        let (p1, v1) = (self.position.map(i128::from), self.velocity.map(i128::from));
+        let (p2, v2) = (
+            other.position.map(i128::from),
+            other.velocity.map(i128::from),
+        );
+
+        // Solves p1 + t1 * v1 = p2 + t2 * v2 by Cramer's rule, keeping every time as a fraction
+        // over `determinant` so the comparisons stay exact.
+        let determinant = v2[X] * v1[Y] - v1[X] * v2[Y];
+        if determinant == 0 {
+            return false;
+        }
+
+        let (dx, dy) = (p2[X] - p1[X], p2[Y] - p1[Y]);
+        let (mut t1, mut t2) = (v2[X] * dy - v2[Y] * dx, v1[X] * dy - v1[Y] * dx);
+        let mut crossing = [X, Y].map(|axis| p1[axis] * determinant + v1[axis] * t1);
+        let (mut min, mut max) = (i128::from(*test_area.start()), i128::from(*test_area.end()));
+        (min, max) = (min * determinant, max * determinant);
+        if determinant < 0 {
+            (t1, t2) = (-t1, -t2);
+            crossing = crossing.map(|coord| -coord);
+            (min, max) = (-min, -max);
+        }
+
+        t1 >= 0 && t2 >= 0 && crossing.iter().all(|coord| (min..=max).contains(coord))
+    }
+
+    /// Returns the time this hailstone and `other` are in the same place, if that happens at a
+    /// whole, non-negative time.
+    fn get_collision_time(&self, other: &Self) -> Option<i64> {
+        let mut time = None;
+        for axis in [X, Y, Z] {
+            let distance = other.position[axis] - self.position[axis];
+            let speed = self.velocity[axis] - other.velocity[axis];
+            match (distance, speed) {
+                (0, 0) => continue,
+                (_, 0) => return None,
+                _ if distance % speed != 0 => return None,
+                _ if time.is_some_and(|time| time != distance / speed) => return None,
+                _ => time = Some(distance / speed),
+            }
+        }
+
+        time.or(Some(0)).filter(|&time| time >= 0)
+    }
+}
+
+fn parse_vector(line: &InputLine, s: &str) -> Result<Vector, ParseError> {
+    let mut coords = s.split(',');
+    let mut vector = Vector::default();
+    for coord in &mut vector {
+        let value = coords
+            .next()
+            .ok_or_else(|| line.error(&s[s.len()..], "three coordinates"))?;
+        *coord = line.parse(value.trim(), "a coordinate")?;
+    }
+
+    match coords.next() {
+        Some(coord) => Err(line.error(coord, "`@` or the end of the line")),
+        None => Ok(vector),
+    }
+}
+
+fn cross(a: [i128; 3], b: [i128; 3]) -> Option<[i128; 3]> {
+    let component =
+        |i: usize, j: usize| a[i].checked_mul(b[j])?.checked_sub(a[j].checked_mul(b[i])?);
+
+    Some([component(Y, Z)?, component(Z, X)?, component(X, Y)?])
+}
+
+fn reduce(vector: [i128; 3]) -> [i128; 3] {
+    let gcd = vector
+        .iter()
+        .fold(0, |gcd, &coord| gcd_of(gcd, coord.abs()));
+    vector.map(|coord| if gcd == 0 { coord } else { coord / gcd })
+}
+
+fn gcd_of(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a
+    } else {
+        gcd_of(b, a % b)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hailstorm {
+    hailstones: Vec<Hailstone>,
+}
+
+impl Hailstorm {
+    #[inline]
+    pub const fn new(hailstones: Vec<Hailstone>) -> Self {
+        Self { hailstones }
+    }
+
+    #[inline]
+    pub fn hailstones(&self) -> &[Hailstone] {
+        &self.hailstones
+    }
+
+    /// Counts the pairs of hailstones whose paths cross inside `test_area`, ignoring the Z axis.
+    pub fn count_crossings_xy(&self, test_area: &RangeInclusive<i64>) -> usize {
+        self.hailstones
+            .iter()
+            .enumerate()
+            .map(|(i, hailstone)| {
+                self.hailstones[i + 1..]
+                    .iter()
+                    .filter(|other| hailstone.crosses_xy_within(other, test_area))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Finds the throw, at a whole position and velocity, that hits every hailstone at a whole,
+    /// non-negative time.
+    ///
+    /// Seen from the first hailstone, the rock's path runs through the origin and meets the
+    /// paths of two other hailstones, so it lies in the planes through the origin and each of
+    /// those paths. Crossing the planes' normals gives the rock's direction, which fixes when it
+    /// hits both hailstones, and so where it starts. Everything is worked out in integers, as
+    /// floats cannot hold these coordinates exactly.
+    pub fn find_rock(&self) -> Option<Hailstone> {
+        let (reference, rest) = self.hailstones.split_first()?;
+        rest.windows(2)
+            .find_map(|pair| self.find_rock_through(reference, &pair[0], &pair[1]))
+    }
+
+    fn find_rock_through(
+        &self,
+        reference: &Hailstone,
+        hailstone1: &Hailstone,
+        hailstone2: &Hailstone,
+    ) -> Option<Hailstone> {
+        let relative = |hailstone: &Hailstone| {
+            let position = [X, Y, Z].map(|axis| {
+                i128::from(hailstone.position[axis]) - i128::from(reference.position[axis])
+            });
+            let velocity = [X, Y, Z].map(|axis| {
+                i128::from(hailstone.velocity[axis]) - i128::from(reference.velocity[axis])
+            });
+            (position, velocity)
+        };
+        let (position1, velocity1) = relative(hailstone1);
+        let (position2, velocity2) = relative(hailstone2);
+
+        let normal1 = reduce(cross(position1, velocity1)?);
+        let normal2 = reduce(cross(position2, velocity2)?);
+        let direction = reduce(cross(normal1, normal2)?);
+
+        // The hailstone is on the rock's path once `position + time * velocity` is parallel to
+        // `direction`.
+        let get_time = |position: [i128; 3], velocity: [i128; 3]| {
+            let offset = cross(position, direction)?;
+            let speed = cross(velocity, direction)?;
+            let mut time = None;
+            for (offset, speed) in offset.into_iter().zip(speed) {
+                match (offset, speed) {
+                    (0, 0) => continue,
+                    (_, 0) => return None,
+                    _ if offset % speed != 0 => return None,
+                    _ if time.is_some_and(|time| time != -offset / speed) => return None,
+                    _ => time = Some(-offset / speed),
+                }
+            }
+
+            time
+        };
+        let time1 = get_time(position1, velocity1)?;
+        let time2 = get_time(position2, velocity2)?;
+        if time1 == time2 {
+            return None;
+        }
+
+        let get_hit = |hailstone: &Hailstone, time: i128| {
+            [X, Y, Z].map(|axis| {
+                i128::from(hailstone.position[axis]) + time * i128::from(hailstone.velocity[axis])
+            })
+        };
+        let (hit1, hit2) = (get_hit(hailstone1, time1), get_hit(hailstone2, time2));
+
+        let mut velocity = Vector::default();
+        let mut position = Vector::default();
+        for axis in [X, Y, Z] {
+            let distance = hit2[axis] - hit1[axis];
+            if distance % (time2 - time1) != 0 {
+                return None;
+            }
+
+            let speed = distance / (time2 - time1);
+            velocity[axis] = speed.try_into().ok()?;
+            position[axis] = (hit1[axis] - time1 * speed).try_into().ok()?;
+        }
+
+        let rock = Hailstone::with(position, velocity);
+        self.hailstones
+            .iter()
+            .all(|hailstone| rock.get_collision_time(hailstone).is_some())
+            .then_some(rock)
+    }
+}
+
+#[aoc_runner_derive::aoc_generator(day24)]
+pub fn generate_input(input: &str) -> Result<Hailstorm, ParseError> {
+    let hailstones = input_lines(DAY, input)
+        .map(|line| Hailstone::parse(&line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Hailstorm::new(hailstones))
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day24, part1)]
+pub fn solve_part1(input: &Hailstorm) -> usize {
+    input.count_crossings_xy(&PART1_TEST_AREA)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day24, part2)]
+pub fn solve_part2(input: &Hailstorm) -> Option<i64> {
+    let rock = input.find_rock()?;
+    Some(rock.position().iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hailstone;
+
+    const SAMPLE_INPUT: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_count_crossings_xy_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        assert_eq!(input.count_crossings_xy(&(7..=27)), 2)
+    }
+
+    #[test]
+    fn test_solve_part1_scaled_sample() {
+        // Scaling the positions by 10^13 scales the times by as much, and shifting them by
+        // 1.3 * 10^14 then moves the sample's test area, 7 to 27, onto the real one.
+        let sample = super::generate_input(SAMPLE_INPUT).unwrap();
+        let hailstones = sample
+            .hailstones()
+            .iter()
+            .map(|hailstone| {
+                let position = hailstone
+                    .position()
+                    .map(|coord| coord * 10_000_000_000_000 + 130_000_000_000_000);
+                Hailstone::with(position, hailstone.velocity())
+            })
+            .collect();
+
+        let input = super::Hailstorm::new(hailstones);
+        assert_eq!(super::solve_part1(&input), 2)
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(SAMPLE_INPUT).unwrap();
+        let rock = input.find_rock().unwrap();
+        assert_eq!(rock, Hailstone::with([24, 13, 10], [-3, 1, 2]));
+        assert_eq!(super::solve_part2(&input), Some(47))
+    }
+
+    #[test]
+    fn test_find_rock_large_coordinates() {
+        let rock = Hailstone::with(
+            [
+                312_604_525_712_345,
+                276_231_064_382_617,
+                198_402_748_571_903,
+            ],
+            [-112, 45, 217],
+        );
+        let hailstones = [
+            (3, [-27, 118, -64]),
+            (17, [301, -9, 12]),
+            (42, [-205, 33, 150]),
+            (96, [88, -140, -311]),
+            (250, [14, 276, 9]),
+        ]
+        .map(|(time, velocity): (i64, [i64; 3])| {
+            let hit = [0, 1, 2].map(|axis| rock.position()[axis] + time * rock.velocity()[axis]);
+            let position = [0, 1, 2].map(|axis| hit[axis] - time * velocity[axis]);
+            Hailstone::with(position, velocity)
+        });
+
+        let input = super::Hailstorm::new(hailstones.to_vec());
+        assert_eq!(input.find_rock(), Some(rock))
+    }
+
+    #[test]
+    fn test_generate_input_errors() {
+        let error = super::generate_input("19, 13, 30 -2, 1, -2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "day 24, line 1, column 21: expected `@`");
+
+        let error = super::generate_input("19, 13 @ -2, 1, -2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 24, line 1, column 8: expected three coordinates"
+        )
+    }
+}
@@ -166,10 +166,7 @@ fn get_gears(lines: &[&str]) -> HashMap<Coords, Vec<u32>> {
                 }
             } else {
                 for &surrounding_gear in &surrounding_gears {
-                    gears
-                        .entry(surrounding_gear)
-                        .or_insert_with(Vec::new)
-                        .push(curr_number);
+                    gears.entry(surrounding_gear).or_default().push(curr_number);
                 }
 
                 curr_number = u32::MIN;
@@ -178,10 +175,7 @@ fn get_gears(lines: &[&str]) -> HashMap<Coords, Vec<u32>> {
         }
 
         for &surrounding_gear in &surrounding_gears {
-            gears
-                .entry(surrounding_gear)
-                .or_insert_with(Vec::new)
-                .push(curr_number);
+            gears.entry(surrounding_gear).or_default().push(curr_number);
         }
     }
 
@@ -1,3 +1,7 @@
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 4;
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Card {
     common_numbers: u128,
@@ -14,14 +18,14 @@ impl Card {
         Self { common_numbers }
     }
 
-    #[inline]
-    pub fn parse_str(s: &str) -> Self {
-        let mut numbers = s.split(':').last().unwrap().split('|');
+    pub fn parse_line(line: &InputLine) -> Result<Self, ParseError> {
+        let (_, numbers) = line.split_once(line.as_str(), ':', "`:`")?;
+        let (winning_numbers, current_numbers) = line.split_once(numbers, '|', "`|`")?;
 
-        let winning_numbers = Card::parse_numbers(numbers.next().unwrap());
-        let current_numbers = Card::parse_numbers(numbers.next().unwrap());
+        let winning_numbers = Card::parse_numbers(line, winning_numbers)?;
+        let current_numbers = Card::parse_numbers(line, current_numbers)?;
 
-        Self::with_common_numbers(winning_numbers & current_numbers)
+        Ok(Self::with_common_numbers(winning_numbers & current_numbers))
     }
 
     #[inline]
@@ -34,23 +38,27 @@ impl Card {
         (1 << self.count_common_numbers()) >> 1
     }
 
-    #[inline]
-    fn parse_numbers(numbers: &str) -> u128 {
+    fn parse_numbers(line: &InputLine, numbers: &str) -> Result<u128, ParseError> {
         let mut number_set = u128::MIN;
-        numbers
-            .trim()
-            .split_ascii_whitespace()
-            .map(|num| num.parse::<u32>().unwrap())
-            .for_each(|num| number_set |= 1 << num);
+        for number in numbers.split_ascii_whitespace() {
+            let num = line.parse::<u32>(number, "a number below 128")?;
+            if num >= u128::BITS {
+                return Err(line.error(number, "a number below 128"));
+            }
 
-        number_set
+            number_set |= 1 << num;
+        }
+
+        Ok(number_set)
     }
 }
 
 #[inline]
 #[aoc_runner_derive::aoc_generator(day4)]
-pub fn generate_input(input: &str) -> Vec<Card> {
-    input.lines().map(Card::parse_str).collect()
+pub fn generate_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    input_lines(DAY, input)
+        .map(|line| Card::parse_line(&line))
+        .collect()
 }
 
 #[inline]
@@ -92,7 +100,8 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        );
+        )
+        .unwrap();
         assert_eq!(super::solve_part1(&input), 13)
     }
 
@@ -105,7 +114,24 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        );
+        )
+        .unwrap();
         assert_eq!(super::solve_part2(&input), 30)
     }
+
+    #[test]
+    fn test_generate_input_errors() {
+        let error = super::generate_input("Card 1: 41 48 | 83 86\nCard 2: 13 32 20 61 30")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "day 4, line 2, column 23: expected `|`");
+
+        let error = super::generate_input("Card 1: 41 128 | 83 86")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 4, line 1, column 12: expected a number below 128"
+        )
+    }
 }
@@ -1,4 +1,13 @@
-use std::{ops::Range, str::Lines};
+use std::{
+    fmt,
+    iter::Peekable,
+    num::{IntErrorKind, ParseIntError},
+    ops::Range,
+};
+
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 5;
 
 pub type SrcRange = Range<i64>;
 pub type DstRange = Range<i64>;
@@ -22,290 +31,777 @@ impl SrcDstRangeMap {
             dst_range,
         }
     }
+
+    #[inline]
+    const fn get_value(&self, key: i64) -> i64 {
+        key + self.dst_range.start - self.src_range.start
+    }
+}
+
+impl fmt::Display for SrcDstRangeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range_len = self.src_range.end - self.src_range.start;
+        write!(
+            f,
+            "{} {} {range_len}",
+            self.dst_range.start, self.src_range.start
+        )
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub struct SmallAlmanac {
-    seeds: Vec<i64>,
-    seed_to_soil: Vec<SrcDstRangeMap>,
-    soil_to_fertilizer: Vec<SrcDstRangeMap>,
-    fertilizer_to_water: Vec<SrcDstRangeMap>,
-    water_to_light: Vec<SrcDstRangeMap>,
-    light_to_temperature: Vec<SrcDstRangeMap>,
-    temperature_to_humidity: Vec<SrcDstRangeMap>,
-    humidity_to_location: Vec<SrcDstRangeMap>,
+pub struct CategoryMap {
+    src_category: String,
+    dst_category: String,
+    maps: Vec<SrcDstRangeMap>,
 }
 
-impl SmallAlmanac {
+impl CategoryMap {
     #[inline]
-    pub fn new() -> Self {
-        Self::with(
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-        )
+    pub const fn new() -> Self {
+        Self::with(String::new(), String::new(), Vec::new())
     }
 
     #[inline]
-    #[allow(clippy::too_many_arguments)]
-    pub fn with(
-        seeds: Vec<i64>,
-        seed_to_soil: Vec<SrcDstRangeMap>,
-        soil_to_fertilizer: Vec<SrcDstRangeMap>,
-        fertilizer_to_water: Vec<SrcDstRangeMap>,
-        water_to_light: Vec<SrcDstRangeMap>,
-        light_to_temperature: Vec<SrcDstRangeMap>,
-        temperature_to_humidity: Vec<SrcDstRangeMap>,
-        humidity_to_location: Vec<SrcDstRangeMap>,
+    pub const fn with(
+        src_category: String,
+        dst_category: String,
+        maps: Vec<SrcDstRangeMap>,
     ) -> Self {
         Self {
-            seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
+            src_category,
+            dst_category,
+            maps,
         }
     }
 
-    pub fn parse_str(s: &str) -> Self {
-        let mut lines = s.lines();
+    #[inline]
+    pub fn src_category(&self) -> &str {
+        &self.src_category
+    }
 
-        let seeds = lines.next().unwrap();
-        let seeds = Self::parse_seeds(seeds);
+    #[inline]
+    pub fn dst_category(&self) -> &str {
+        &self.dst_category
+    }
 
-        _ = lines.nth(1);
-        let seed_to_soil = Self::parse_maps(&mut lines);
+    #[inline]
+    pub fn maps(&self) -> &[SrcDstRangeMap] {
+        &self.maps
+    }
 
-        _ = lines.next();
-        let soil_to_fertilizer = Self::parse_maps(&mut lines);
+    #[inline]
+    fn get_value_or(&self, key_and_default: i64) -> i64 {
+        self.trace_value_or(key_and_default).0
+    }
 
-        _ = lines.next();
-        let fertilizer_to_water = Self::parse_maps(&mut lines);
+    fn trace_value_or(&self, key_and_default: i64) -> (i64, MapMatch) {
+        self.maps
+            .iter()
+            .enumerate()
+            .find(|(_, map)| map.src_range.contains(&key_and_default))
+            .map_or((key_and_default, MapMatch::Identity), |(index, map)| {
+                let map_match = MapMatch::Map {
+                    index,
+                    map: map.clone(),
+                };
+                (map.get_value(key_and_default), map_match)
+            })
+    }
 
-        _ = lines.next();
-        let water_to_light = Self::parse_maps(&mut lines);
+    /// Splits every key range at the source range boundaries of the maps, shifting the parts
+    /// covered by a map and passing the rest through unchanged. Like [`Self::get_value_or`],
+    /// the first map covering a key wins.
+    #[inline]
+    fn get_ranges_or(&self, keys_and_defaults: Vec<SrcRange>) -> Vec<DstRange> {
+        self.trace_ranges_or(keys_and_defaults)
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect()
+    }
 
-        _ = lines.next();
-        let light_to_temperature = Self::parse_maps(&mut lines);
+    fn trace_ranges_or(&self, keys_and_defaults: Vec<SrcRange>) -> Vec<(DstRange, MapMatch)> {
+        let mut unmapped_ranges = keys_and_defaults;
+        unmapped_ranges.retain(|range| !range.is_empty());
+
+        let mut mapped_ranges = Vec::new();
+        for (index, map) in self.maps.iter().enumerate() {
+            let mut remaining_ranges = Vec::new();
+            for range in unmapped_ranges {
+                let overlap_start = range.start.max(map.src_range.start);
+                let overlap_end = range.end.min(map.src_range.end);
+
+                if overlap_start >= overlap_end {
+                    remaining_ranges.push(range);
+                    continue;
+                }
+
+                if range.start < overlap_start {
+                    remaining_ranges.push(range.start..overlap_start);
+                }
+                if overlap_end < range.end {
+                    remaining_ranges.push(overlap_end..range.end);
+                }
+
+                let dst_range_start = map.get_value(overlap_start);
+                let dst_range_end = map.get_value(overlap_end);
+                let map_match = MapMatch::Map {
+                    index,
+                    map: map.clone(),
+                };
+                mapped_ranges.push((dst_range_start..dst_range_end, map_match));
+            }
+
+            unmapped_ranges = remaining_ranges;
+        }
 
-        _ = lines.next();
-        let temperature_to_humidity = Self::parse_maps(&mut lines);
+        mapped_ranges.extend(
+            unmapped_ranges
+                .into_iter()
+                .map(|range| (range, MapMatch::Identity)),
+        );
+        mapped_ranges
+    }
+}
 
-        _ = lines.next();
-        let humidity_to_location = Self::parse_maps(&mut lines);
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Segment {
+    src_range: SrcRange,
+    offset: i64,
+}
 
-        Self::with(
-            seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
-        )
+impl Segment {
+    #[inline]
+    pub const fn with(src_range: SrcRange, offset: i64) -> Self {
+        Self { src_range, offset }
     }
 
     #[inline]
-    fn parse_seeds(seeds: &str) -> Vec<i64> {
-        seeds
-            .split(':')
-            .last()
-            .unwrap()
-            .trim()
-            .split_ascii_whitespace()
-            .map(|num| num.parse().unwrap())
-            .collect()
+    pub const fn src_range(&self) -> &SrcRange {
+        &self.src_range
     }
 
-    fn parse_maps(lines: &mut Lines) -> Vec<SrcDstRangeMap> {
-        let mut maps = Vec::new();
-        for line in lines.take_while(|&line| !line.is_empty()) {
-            let (src_range_start, dst_range_start, range_len) = Self::parse_range(line);
+    #[inline]
+    pub const fn offset(&self) -> i64 {
+        self.offset
+    }
+}
 
-            let src_range_end = src_range_start + range_len;
-            let dst_range_end = dst_range_start + range_len;
+/// A map made of sorted, disjoint segments that each shift their source range by an offset.
+/// Keys outside every segment map to themselves. Its `Display` output is the `dst src len` lines
+/// of an almanac map section.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    segments: Vec<Segment>,
+}
 
-            let src_range = src_range_start..src_range_end;
-            let dst_range = dst_range_start..dst_range_end;
+impl PiecewiseMap {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
 
-            let map = SrcDstRangeMap::with(src_range, dst_range);
-            maps.push(map);
+    #[inline]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Builds the map from `cut_points`, which must include every point where `get_offset` may
+    /// change. Identity segments are dropped and equal neighbours are merged.
+    fn from_cut_points(mut cut_points: Vec<i64>, get_offset: impl Fn(i64) -> i64) -> Self {
+        cut_points.sort_unstable();
+        cut_points.dedup();
+
+        let mut segments = Vec::<Segment>::new();
+        for window in cut_points.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let offset = get_offset(start);
+            if offset == 0 {
+                continue;
+            }
+
+            match segments.last_mut() {
+                Some(last) if last.src_range.end == start && last.offset == offset => {
+                    last.src_range.end = end;
+                }
+                _ => segments.push(Segment::with(start..end, offset)),
+            }
         }
 
-        maps
+        Self { segments }
     }
 
     #[inline]
-    fn parse_range(range: &str) -> (i64, i64, i64) {
-        let range = range
-            .trim()
-            .split_ascii_whitespace()
-            .map(|num| num.parse().unwrap())
-            .collect::<Vec<i64>>();
+    fn get_offset(&self, key: i64) -> i64 {
+        let i = self
+            .segments
+            .partition_point(|segment| segment.src_range.end <= key);
+
+        self.segments
+            .get(i)
+            .filter(|segment| segment.src_range.contains(&key))
+            .map_or(0, |segment| segment.offset)
+    }
 
-        (range[1], range[0], range[2])
+    #[inline]
+    pub fn get_value(&self, key: i64) -> i64 {
+        key + self.get_offset(key)
     }
 
-    fn get_nearest_location(&self) -> i64 {
-        self.seeds
+    /// Returns the map that applies `self` first and `other` second.
+    pub fn then(&self, other: &Self) -> Self {
+        let offsets = self
+            .segments
             .iter()
-            .map(|&seed| self.get_seed_location(seed))
-            .min()
-            .unwrap()
+            .map(|segment| segment.offset)
+            .chain([0])
+            .collect::<Vec<_>>();
+
+        let mut cut_points = self.get_boundaries().collect::<Vec<_>>();
+        for boundary in other.get_boundaries() {
+            cut_points.extend(
+                offsets
+                    .iter()
+                    .filter_map(|&offset| boundary.checked_sub(offset)),
+            );
+        }
+
+        Self::from_cut_points(cut_points, |key| {
+            let offset = self.get_offset(key);
+            offset + other.get_offset(key + offset)
+        })
     }
 
-    fn get_seed_location(&self, seed: i64) -> i64 {
-        let soil = Self::get_map_value_or(&self.seed_to_soil, seed);
-        let fertilizer = Self::get_map_value_or(&self.soil_to_fertilizer, soil);
-        let water = Self::get_map_value_or(&self.fertilizer_to_water, fertilizer);
-        let light = Self::get_map_value_or(&self.water_to_light, water);
-        let temperature = Self::get_map_value_or(&self.light_to_temperature, light);
-        let humidity = Self::get_map_value_or(&self.temperature_to_humidity, temperature);
+    /// Returns every key that maps to `value`, in ascending order.
+    pub fn get_inverse_values(&self, value: i64) -> Vec<i64> {
+        let mut keys = self
+            .segments
+            .iter()
+            .filter_map(|segment| {
+                let key = value.checked_sub(segment.offset)?;
+                segment.src_range.contains(&key).then_some(key)
+            })
+            .collect::<Vec<_>>();
 
-        Self::get_map_value_or(&self.humidity_to_location, humidity)
+        if self.get_offset(value) == 0 {
+            keys.push(value);
+        }
+
+        keys.sort_unstable();
+        keys
     }
 
-    #[inline]
-    fn get_map_value_or(maps: &[SrcDstRangeMap], key_and_default: i64) -> i64 {
-        maps.iter()
-            .find(|&map| map.src_range.contains(&key_and_default))
-            .map_or(key_and_default, |map| {
-                Self::get_map_value(map, key_and_default)
+    /// Returns the ranges of keys that map into `value_range`, in ascending order.
+    pub fn get_inverse_ranges(&self, value_range: &DstRange) -> Vec<SrcRange> {
+        let mut key_ranges = self
+            .get_pieces()
+            .into_iter()
+            .filter_map(|(src_range, offset)| {
+                // Saturating is exact here, as every key lies within the i64 range anyway.
+                let start = src_range
+                    .start
+                    .max(value_range.start.saturating_sub(offset));
+                let end = src_range.end.min(value_range.end.saturating_sub(offset));
+                (start < end).then_some(start..end)
             })
+            .collect::<Vec<_>>();
+
+        key_ranges.sort_unstable_by_key(|key_range| key_range.start);
+        key_ranges
+    }
+
+    /// Returns the segments together with the identity gaps around and between them.
+    fn get_pieces(&self) -> Vec<(SrcRange, i64)> {
+        let mut pieces = Vec::with_capacity(2 * self.segments.len() + 1);
+        let mut gap_start = i64::MIN;
+        for segment in &self.segments {
+            if gap_start < segment.src_range.start {
+                pieces.push((gap_start..segment.src_range.start, 0));
+            }
+
+            pieces.push((segment.src_range.clone(), segment.offset));
+            gap_start = segment.src_range.end;
+        }
+        pieces.push((gap_start..i64::MAX, 0));
+
+        pieces
     }
 
+    /// Returns the sorted points where the set of pieces mapping onto a value changes, from
+    /// `i64::MIN` to `i64::MAX`.
+    fn get_value_boundaries(&self) -> Vec<i64> {
+        let mut boundaries = self
+            .get_pieces()
+            .into_iter()
+            .flat_map(|(src_range, offset)| {
+                [src_range.start, src_range.end].map(|key| key.saturating_add(offset))
+            })
+            .collect::<Vec<_>>();
+
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+    }
+
+    fn get_boundaries(&self) -> impl Iterator<Item = i64> + '_ {
+        self.segments
+            .iter()
+            .flat_map(|segment| [segment.src_range.start, segment.src_range.end])
+    }
+}
+
+impl From<&CategoryMap> for PiecewiseMap {
+    fn from(category_map: &CategoryMap) -> Self {
+        let cut_points = category_map
+            .maps
+            .iter()
+            .flat_map(|map| [map.src_range.start, map.src_range.end])
+            .collect();
+
+        Self::from_cut_points(cut_points, |key| category_map.get_value_or(key) - key)
+    }
+}
+
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            let src_range = &segment.src_range;
+            let dst_range_start = src_range.start + segment.offset;
+            let range_len = src_range.end - src_range.start;
+            write!(f, "{dst_range_start} {} {range_len}", src_range.start)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    MalformedHeader,
+    FieldCount {
+        found: usize,
+    },
+    NonNumericField {
+        field: String,
+    },
+    FieldOutOfRange {
+        field: String,
+    },
+    NonPositiveLength {
+        len: i64,
+    },
+    RangeEndOutOfRange,
+    Overlap {
+        other_line_no: usize,
+    },
+    Gap {
+        range: SrcRange,
+    },
+    Unexpected {
+        column: usize,
+        expected: &'static str,
+    },
+}
+
+impl DiagnosticKind {
+    /// Gaps are valid (keys in them map to themselves) so they are only reported as warnings.
     #[inline]
-    const fn get_map_value(map: &SrcDstRangeMap, key_and_default: i64) -> i64 {
-        key_and_default + map.dst_range.start - map.src_range.start
+    pub const fn is_error(&self) -> bool {
+        !matches!(self, Self::Gap { .. })
     }
 }
 
-#[inline]
-#[aoc_runner_derive::aoc(day5, part1)]
-pub fn solve_part1(input: &str) -> i64 {
-    SmallAlmanac::parse_str(input).get_nearest_location()
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedHeader => write!(f, "expected a `<from>-to-<to> map:` header"),
+            Self::FieldCount { found } => write!(f, "expected 3 fields, found {found}"),
+            Self::NonNumericField { field } => write!(f, "`{field}` is not a number"),
+            Self::FieldOutOfRange { field } => write!(f, "`{field}` does not fit in an i64"),
+            Self::NonPositiveLength { len } => write!(f, "range length {len} is not positive"),
+            Self::RangeEndOutOfRange => write!(f, "range end does not fit in an i64"),
+            Self::Overlap { other_line_no } => {
+                write!(f, "source range overlaps the one on line {other_line_no}")
+            }
+            Self::Unexpected { column, expected } => {
+                write!(f, "expected {expected} at column {column}")
+            }
+            Self::Gap { range } => {
+                write!(
+                    f,
+                    "source ranges leave a gap at {}..{}",
+                    range.start, range.end
+                )
+            }
+        }
+    }
 }
 
-pub struct BigAlmanac {
-    seed_ranges: Vec<SrcRange>,
-    seed_to_soil: Vec<SrcDstRangeMap>,
-    soil_to_fertilizer: Vec<SrcDstRangeMap>,
-    fertilizer_to_water: Vec<SrcDstRangeMap>,
-    water_to_light: Vec<SrcDstRangeMap>,
-    light_to_temperature: Vec<SrcDstRangeMap>,
-    temperature_to_humidity: Vec<SrcDstRangeMap>,
-    humidity_to_location: Vec<SrcDstRangeMap>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    map_name: String,
+    line_no: usize,
+    kind: DiagnosticKind,
 }
 
-impl BigAlmanac {
+impl Diagnostic {
     #[inline]
-    pub const fn new() -> Self {
-        Self::with(
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
+    pub const fn with(map_name: String, line_no: usize, kind: DiagnosticKind) -> Self {
+        Self {
+            map_name,
+            line_no,
+            kind,
+        }
+    }
+
+    #[inline]
+    pub fn map_name(&self) -> &str {
+        &self.map_name
+    }
+
+    #[inline]
+    pub const fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    #[inline]
+    pub const fn kind(&self) -> &DiagnosticKind {
+        &self.kind
+    }
+
+    #[inline]
+    pub const fn is_error(&self) -> bool {
+        self.kind.is_error()
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(err: ParseError) -> Self {
+        let kind = DiagnosticKind::Unexpected {
+            column: err.column(),
+            expected: err.expected(),
+        };
+
+        Self::with("almanac".to_owned(), err.line_no(), kind)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.is_error() { "error" } else { "warning" };
+        write!(
+            f,
+            "{severity}: line {} ({}): {}",
+            self.line_no, self.map_name, self.kind
         )
     }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapMatch {
+    /// The key fell inside the map at `index` of its section.
+    Map { index: usize, map: SrcDstRangeMap },
+    /// No map covered the key, so it kept its value.
+    Identity,
+}
+
+impl fmt::Display for MapMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Map { index, map } => write!(f, "#{}: {map}", index + 1),
+            Self::Identity => write!(f, "identity"),
+        }
+    }
+}
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    category: String,
+    value: i64,
+    map_match: Option<MapMatch>,
+}
+
+impl TraceStep {
     #[inline]
-    #[allow(clippy::too_many_arguments)]
-    pub const fn with(
-        seed_ranges: Vec<SrcRange>,
-        seed_to_soil: Vec<SrcDstRangeMap>,
-        soil_to_fertilizer: Vec<SrcDstRangeMap>,
-        fertilizer_to_water: Vec<SrcDstRangeMap>,
-        water_to_light: Vec<SrcDstRangeMap>,
-        light_to_temperature: Vec<SrcDstRangeMap>,
-        temperature_to_humidity: Vec<SrcDstRangeMap>,
-        humidity_to_location: Vec<SrcDstRangeMap>,
-    ) -> Self {
+    pub const fn with(category: String, value: i64, map_match: Option<MapMatch>) -> Self {
         Self {
-            seed_ranges,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
+            category,
+            value,
+            map_match,
         }
     }
 
-    pub fn parse_str(s: &str) -> Self {
-        let mut lines = s.lines();
+    #[inline]
+    pub fn category(&self) -> &str {
+        &self.category
+    }
 
-        let seed_ranges = lines.next().unwrap();
-        let seed_ranges = Self::parse_seed_ranges(seed_ranges);
+    #[inline]
+    pub const fn value(&self) -> i64 {
+        self.value
+    }
 
-        _ = lines.nth(1);
-        let seed_to_soil = Self::parse_maps(&mut lines);
+    /// Returns how the value was reached from the previous step, or `None` for the seed itself.
+    #[inline]
+    pub const fn map_match(&self) -> Option<&MapMatch> {
+        self.map_match.as_ref()
+    }
+}
 
-        _ = lines.next();
-        let soil_to_fertilizer = Self::parse_maps(&mut lines);
+/// The value of a single seed in every category of an almanac, starting with the seed itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SeedTrace {
+    steps: Vec<TraceStep>,
+}
 
-        _ = lines.next();
-        let fertilizer_to_water = Self::parse_maps(&mut lines);
+impl SeedTrace {
+    #[inline]
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+}
 
-        _ = lines.next();
-        let water_to_light = Self::parse_maps(&mut lines);
+impl fmt::Display for SeedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = Vec::with_capacity(self.steps.len());
+        let mut src_category = "";
+        for step in &self.steps {
+            rows.push(get_trace_row(
+                step.category.clone(),
+                src_category,
+                step.value.to_string(),
+                step.map_match.as_ref(),
+            ));
+            src_category = &step.category;
+        }
+
+        write_trace_table(f, ["category", "value", "map"], &rows)
+    }
+}
 
-        _ = lines.next();
-        let light_to_temperature = Self::parse_maps(&mut lines);
+/// A range of one category together with the sub-ranges the next category map splits it into.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeTrace {
+    category: String,
+    range: SrcRange,
+    map_match: Option<MapMatch>,
+    children: Vec<RangeTrace>,
+}
 
-        _ = lines.next();
-        let temperature_to_humidity = Self::parse_maps(&mut lines);
+impl RangeTrace {
+    #[inline]
+    pub fn category(&self) -> &str {
+        &self.category
+    }
 
-        _ = lines.next();
-        let humidity_to_location = Self::parse_maps(&mut lines);
+    #[inline]
+    pub const fn range(&self) -> &SrcRange {
+        &self.range
+    }
 
-        Self::with(
-            seed_ranges,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
-        )
+    /// Returns how the range was reached from its parent, or `None` for the seed range itself.
+    #[inline]
+    pub const fn map_match(&self) -> Option<&MapMatch> {
+        self.map_match.as_ref()
     }
 
     #[inline]
-    fn parse_seed_ranges(seed_ranges: &str) -> Vec<SrcRange> {
-        seed_ranges
-            .split(':')
-            .last()
-            .unwrap()
-            .trim()
-            .split_ascii_whitespace()
-            .map(|num| num.parse().unwrap())
-            .collect::<Vec<_>>()
-            .chunks_exact(2)
-            .map(|chunk| chunk[0]..(chunk[0] + chunk[1]))
-            .collect()
+    pub fn children(&self) -> &[RangeTrace] {
+        &self.children
+    }
+
+    fn push_rows(&self, depth: usize, src_category: &str, rows: &mut Vec<[String; 3]>) {
+        let category = format!("{:indent$}{}", "", self.category, indent = 2 * depth);
+        let range = format!("{}..{}", self.range.start, self.range.end);
+        rows.push(get_trace_row(
+            category,
+            src_category,
+            range,
+            self.map_match.as_ref(),
+        ));
+
+        for child in &self.children {
+            child.push_rows(depth + 1, &self.category, rows);
+        }
+    }
+}
+
+impl fmt::Display for RangeTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = Vec::new();
+        self.push_rows(0, "", &mut rows);
+
+        write_trace_table(f, ["category", "range", "map"], &rows)
+    }
+}
+
+fn get_trace_row(
+    category: String,
+    src_category: &str,
+    value: String,
+    map_match: Option<&MapMatch>,
+) -> [String; 3] {
+    let map_match = map_match.map_or_else(String::new, |map_match| {
+        format!("{src_category}-to-{} {map_match}", category.trim_start())
+    });
+
+    [category, value, map_match]
+}
+
+fn write_trace_table(
+    f: &mut fmt::Formatter<'_>,
+    header: [&str; 3],
+    rows: &[[String; 3]],
+) -> fmt::Result {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let [category_width, value_width, _] = widths;
+    write!(
+        f,
+        "{:category_width$}  {:value_width$}  {}",
+        header[0], header[1], header[2]
+    )?;
+    for [category, value, map_match] in rows {
+        let row = format!("{category:category_width$}  {value:value_width$}  {map_match}");
+        writeln!(f)?;
+        write!(f, "{}", row.trim_end())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Almanac {
+    seeds: Vec<i64>,
+    category_maps: Vec<CategoryMap>,
+}
+
+impl Almanac {
+    const SEED_CATEGORY: &'static str = "seed";
+
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with(Vec::new(), Vec::new())
+    }
+
+    /// Creates an almanac from category maps that are already chained, i.e. the first map starts
+    /// at `seed` and every map starts where the previous one ends.
+    #[inline]
+    pub const fn with(seeds: Vec<i64>, category_maps: Vec<CategoryMap>) -> Self {
+        Self {
+            seeds,
+            category_maps,
+        }
+    }
+
+    /// Parses the seeds and every `<from>-to-<to> map:` section, in any order, and chains the
+    /// sections starting from `seed`.
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut lines = input_lines(DAY, s).peekable();
+
+        let seeds = lines
+            .next()
+            .ok_or(ParseError::new(DAY, 1, 1, "a `seeds:` line"))?;
+        let seeds = Self::parse_seeds(&seeds)?;
+
+        let mut category_maps = Vec::new();
+        while let Some(line) = lines.next() {
+            if line.as_str().trim().is_empty() {
+                continue;
+            }
+
+            let (src_category, dst_category) = Self::parse_header(&line)?;
+            let maps = Self::parse_maps(&mut lines)?;
+
+            let category_map = CategoryMap::with(src_category, dst_category, maps);
+            category_maps.push((line, category_map));
+        }
+
+        Ok(Self::with(seeds, Self::chain_category_maps(category_maps)?))
+    }
+
+    /// Like [`Self::parse_str`], but rejects almanacs for which [`Self::validate`] reports any
+    /// error.
+    pub fn parse_str_strict(s: &str) -> Result<Self, Vec<Diagnostic>> {
+        let errors = Self::validate(s)
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Self::parse_str(s).map_err(|err| vec![err.into()])
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parses the seed numbers, which must also read as `start len` seed ranges.
+    fn parse_seeds(line: &InputLine) -> Result<Vec<i64>, ParseError> {
+        let (_, seeds) = line.split_once(line.as_str(), ':', "`seeds:`")?;
+        let fields = seeds.split_ascii_whitespace().collect::<Vec<_>>();
+        if fields.is_empty() {
+            return Err(line.error_at_end("at least one seed number"));
+        }
+
+        let mut seeds = Vec::with_capacity(fields.len());
+        for pair in fields.chunks(2) {
+            let &[start_field, len_field] = pair else {
+                return Err(line.error_at_end("a seed range length"));
+            };
+
+            let start = line.parse::<i64>(start_field, "a seed number")?;
+            let len = line.parse::<i64>(len_field, "a seed number")?;
+            if len <= 0 {
+                return Err(line.error(len_field, "a positive seed range length"));
+            }
+            if start.checked_add(len).is_none() {
+                return Err(line.error(len_field, "a seed range that fits in an i64"));
+            }
+
+            seeds.extend([start, len]);
+        }
+
+        Ok(seeds)
+    }
+
+    #[inline]
+    fn parse_header(line: &InputLine) -> Result<(String, String), ParseError> {
+        const EXPECTED: &str = "a `<from>-to-<to> map:` header";
+
+        let header = line.as_str().trim();
+        let (src_category, dst_category) = header
+            .strip_suffix("map:")
+            .and_then(|header| header.trim().split_once("-to-"))
+            .ok_or_else(|| line.error(header, EXPECTED))?;
+
+        Ok((src_category.to_owned(), dst_category.to_owned()))
     }
 
-    fn parse_maps(lines: &mut Lines) -> Vec<SrcDstRangeMap> {
+    fn parse_maps<'a>(
+        lines: &mut Peekable<impl Iterator<Item = InputLine<'a>>>,
+    ) -> Result<Vec<SrcDstRangeMap>, ParseError> {
         let mut maps = Vec::new();
-        for line in lines.take_while(|&line| !line.is_empty()) {
-            let (src_range_start, dst_range_start, range_len) = Self::parse_range(line);
+        while let Some(line) = lines.next_if(|line| !line.as_str().trim().is_empty()) {
+            let (src_range_start, dst_range_start, range_len) = Self::parse_range(&line)?;
 
-            let src_range_end = src_range_start + range_len;
-            let dst_range_end = dst_range_start + range_len;
+            let range_len_error = || line.error(line.as_str(), "a range that fits in an i64");
+            let src_range_end = src_range_start
+                .checked_add(range_len)
+                .ok_or_else(range_len_error)?;
+            let dst_range_end = dst_range_start
+                .checked_add(range_len)
+                .ok_or_else(range_len_error)?;
 
             let src_range = src_range_start..src_range_end;
             let dst_range = dst_range_start..dst_range_end;
@@ -314,73 +810,359 @@ impl BigAlmanac {
             maps.push(map);
         }
 
-        maps
+        Ok(maps)
     }
 
     #[inline]
-    fn parse_range(range: &str) -> (i64, i64, i64) {
-        let range = range
-            .trim()
-            .split_ascii_whitespace()
-            .map(|num| num.parse().unwrap())
-            .collect::<Vec<i64>>();
+    fn parse_range(line: &InputLine) -> Result<(i64, i64, i64), ParseError> {
+        const EXPECTED: &str = "a `dst src len` range";
+
+        let mut fields = line.as_str().split_ascii_whitespace();
+        let mut range = [i64::MIN; 3];
+        for num in &mut range {
+            let field = fields.next().ok_or_else(|| line.error_at_end(EXPECTED))?;
+            *num = line.parse(field, "a number")?;
+        }
+
+        if let Some(field) = fields.next() {
+            return Err(line.error(field, "the end of the line"));
+        }
 
-        (range[1], range[0], range[2])
+        Ok((range[1], range[0], range[2]))
     }
 
-    fn get_nearest_location(&self) -> i64 {
-        self.seed_ranges
+    fn chain_category_maps(
+        mut category_maps: Vec<(InputLine, CategoryMap)>,
+    ) -> Result<Vec<CategoryMap>, ParseError> {
+        let mut chain = Vec::with_capacity(category_maps.len());
+        let mut curr_category = Self::SEED_CATEGORY.to_owned();
+
+        while let Some(i) = category_maps
             .iter()
-            .map(|seed_range| self.get_nearest_seed_location(seed_range))
-            .min()
-            .unwrap()
+            .position(|(_, category_map)| category_map.src_category == curr_category)
+        {
+            let (_, category_map) = category_maps.remove(i);
+            curr_category.clone_from(&category_map.dst_category);
+            chain.push(category_map);
+        }
+
+        match category_maps.first() {
+            Some((line, _)) => Err(line.error(line.as_str(), "a map reachable from `seed`")),
+            None => Ok(chain),
+        }
     }
 
-    fn get_nearest_seed_location(&self, seed_range: &SrcRange) -> i64 {
-        seed_range
-            .clone()
-            .map(|seed| self.get_seed_location(seed))
-            .min()
-            .unwrap()
+    /// Checks the raw almanac text and reports malformed lines, overlapping source ranges and
+    /// gaps between source ranges, ordered by line number.
+    pub fn validate(s: &str) -> Vec<Diagnostic> {
+        const SEEDS_NAME: &str = "seeds";
+
+        let mut diagnostics = Vec::new();
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .peekable();
+
+        if let Some((line_no, seeds)) = lines.next() {
+            for kind in Self::validate_seeds(seeds) {
+                diagnostics.push(Diagnostic::with(SEEDS_NAME.to_owned(), line_no, kind));
+            }
+        }
+
+        while let Some((line_no, line)) = lines.next() {
+            let header = line.trim();
+            if header.is_empty() {
+                continue;
+            }
+
+            let map_name = match header.strip_suffix("map:").map(str::trim) {
+                Some(map_name) if map_name.contains("-to-") => map_name,
+                _ => {
+                    let kind = DiagnosticKind::MalformedHeader;
+                    diagnostics.push(Diagnostic::with(header.to_owned(), line_no, kind));
+                    header
+                }
+            };
+
+            let mut src_ranges = Vec::new();
+            while let Some((line_no, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+                match Self::validate_range(line) {
+                    Ok(src_range) => src_ranges.push((line_no, src_range)),
+                    Err(kind) => {
+                        diagnostics.push(Diagnostic::with(map_name.to_owned(), line_no, kind))
+                    }
+                }
+            }
+
+            Self::validate_src_ranges(map_name, src_ranges, &mut diagnostics);
+        }
+
+        diagnostics.sort_by_key(Diagnostic::line_no);
+        diagnostics
     }
 
-    fn get_seed_location(&self, seed: i64) -> i64 {
-        let soil = Self::get_map_value_or(&self.seed_to_soil, seed);
-        let fertilizer = Self::get_map_value_or(&self.soil_to_fertilizer, soil);
-        let water = Self::get_map_value_or(&self.fertilizer_to_water, fertilizer);
-        let light = Self::get_map_value_or(&self.water_to_light, water);
-        let temperature = Self::get_map_value_or(&self.light_to_temperature, light);
-        let humidity = Self::get_map_value_or(&self.temperature_to_humidity, temperature);
+    fn validate_seeds(line: &str) -> Vec<DiagnosticKind> {
+        let seeds = line.split(':').next_back().unwrap_or_default();
+        let fields = seeds.split_ascii_whitespace().collect::<Vec<_>>();
+
+        let mut kinds = Vec::new();
+        for pair in fields.chunks(2) {
+            let (start, len) = (Self::validate_number(pair[0]), pair.get(1).copied());
+            let Some(len) = len else {
+                kinds.extend(start.err());
+                kinds.push(DiagnosticKind::Unexpected {
+                    column: line.len() + 1,
+                    expected: "a seed range length",
+                });
+                break;
+            };
+
+            match (start, Self::validate_number(len)) {
+                (Ok(_), Ok(len)) if len <= 0 => {
+                    kinds.push(DiagnosticKind::NonPositiveLength { len });
+                }
+                (Ok(start), Ok(len)) if start.checked_add(len).is_none() => {
+                    kinds.push(DiagnosticKind::RangeEndOutOfRange);
+                }
+                (start, len) => kinds.extend(start.err().into_iter().chain(len.err())),
+            }
+        }
 
-        Self::get_map_value_or(&self.humidity_to_location, humidity)
+        kinds
     }
 
-    #[inline]
-    fn get_map_value_or(maps: &[SrcDstRangeMap], key_and_default: i64) -> i64 {
-        maps.iter()
-            .find(|&map| map.src_range.contains(&key_and_default))
-            .map_or(key_and_default, |map| {
-                Self::get_map_value(map, key_and_default)
+    fn validate_number(field: &str) -> Result<i64, DiagnosticKind> {
+        field
+            .parse()
+            .map_err(|err: ParseIntError| match err.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    DiagnosticKind::FieldOutOfRange {
+                        field: field.to_owned(),
+                    }
+                }
+                _ => DiagnosticKind::NonNumericField {
+                    field: field.to_owned(),
+                },
             })
     }
 
+    fn validate_range(range: &str) -> Result<SrcRange, DiagnosticKind> {
+        let fields = range.split_ascii_whitespace().collect::<Vec<_>>();
+        if fields.len() != 3 {
+            return Err(DiagnosticKind::FieldCount {
+                found: fields.len(),
+            });
+        }
+
+        let dst_range_start = Self::validate_number(fields[0])?;
+        let src_range_start = Self::validate_number(fields[1])?;
+        let range_len = Self::validate_number(fields[2])?;
+
+        if range_len <= 0 {
+            return Err(DiagnosticKind::NonPositiveLength { len: range_len });
+        }
+
+        let src_range_end = src_range_start.checked_add(range_len);
+        let dst_range_end = dst_range_start.checked_add(range_len);
+        match (src_range_end, dst_range_end) {
+            (Some(src_range_end), Some(_)) => Ok(src_range_start..src_range_end),
+            _ => Err(DiagnosticKind::RangeEndOutOfRange),
+        }
+    }
+
+    fn validate_src_ranges(
+        map_name: &str,
+        mut src_ranges: Vec<(usize, SrcRange)>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        src_ranges.sort_by_key(|(line_no, src_range)| (src_range.start, *line_no));
+
+        let mut covered = Option::<(usize, i64)>::None;
+        for (line_no, src_range) in src_ranges {
+            let kind = match covered {
+                Some((other_line_no, covered_end)) if src_range.start < covered_end => {
+                    Some(DiagnosticKind::Overlap { other_line_no })
+                }
+                Some((_, covered_end)) if covered_end < src_range.start => {
+                    Some(DiagnosticKind::Gap {
+                        range: covered_end..src_range.start,
+                    })
+                }
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                diagnostics.push(Diagnostic::with(map_name.to_owned(), line_no, kind));
+            }
+
+            match covered {
+                Some((_, covered_end)) if src_range.end <= covered_end => {}
+                _ => covered = Some((line_no, src_range.end)),
+            }
+        }
+    }
+
     #[inline]
-    const fn get_map_value(map: &SrcDstRangeMap, key_and_default: i64) -> i64 {
-        key_and_default + map.dst_range.start - map.src_range.start
+    pub fn category_maps(&self) -> &[CategoryMap] {
+        &self.category_maps
+    }
+
+    #[inline]
+    pub fn seeds(&self) -> &[i64] {
+        &self.seeds
+    }
+
+    /// Reads the seeds as `start len` pairs, skipping any whose end does not fit in an i64.
+    /// [`Self::parse_str`] rejects such pairs, but [`Self::with`] does not check them.
+    pub fn seed_ranges(&self) -> Vec<SrcRange> {
+        self.seeds
+            .chunks_exact(2)
+            .filter_map(|chunk| Some(chunk[0]..chunk[0].checked_add(chunk[1])?))
+            .collect()
+    }
+
+    pub fn get_seed_location(&self, seed: i64) -> i64 {
+        self.category_maps
+            .iter()
+            .fold(seed, |key, category_map| category_map.get_value_or(key))
+    }
+
+    pub fn get_seed_range_locations(&self, seed_ranges: Vec<SrcRange>) -> Vec<DstRange> {
+        self.category_maps
+            .iter()
+            .fold(seed_ranges, |key_ranges, category_map| {
+                category_map.get_ranges_or(key_ranges)
+            })
+    }
+
+    pub fn trace(&self, seed: i64) -> SeedTrace {
+        let mut steps = vec![TraceStep::with(Self::SEED_CATEGORY.to_owned(), seed, None)];
+        let mut value = seed;
+        for category_map in &self.category_maps {
+            let map_match;
+            (value, map_match) = category_map.trace_value_or(value);
+
+            let category = category_map.dst_category.clone();
+            steps.push(TraceStep::with(category, value, Some(map_match)));
+        }
+
+        SeedTrace { steps }
+    }
+
+    pub fn trace_range(&self, seed_range: SrcRange) -> RangeTrace {
+        let category = Self::SEED_CATEGORY.to_owned();
+        let children = Self::trace_range_children(&self.category_maps, seed_range.clone());
+
+        RangeTrace {
+            category,
+            range: seed_range,
+            map_match: None,
+            children,
+        }
+    }
+
+    fn trace_range_children(category_maps: &[CategoryMap], range: SrcRange) -> Vec<RangeTrace> {
+        let Some((category_map, next_category_maps)) = category_maps.split_first() else {
+            return Vec::new();
+        };
+
+        category_map
+            .trace_ranges_or(vec![range])
+            .into_iter()
+            .map(|(range, map_match)| RangeTrace {
+                category: category_map.dst_category.clone(),
+                range: range.clone(),
+                map_match: Some(map_match),
+                children: Self::trace_range_children(next_category_maps, range),
+            })
+            .collect()
+    }
+
+    /// Folds every category map into a single seed-to-last-category map.
+    pub fn compose(&self) -> PiecewiseMap {
+        self.category_maps
+            .iter()
+            .fold(PiecewiseMap::new(), |composed_map, category_map| {
+                composed_map.then(&category_map.into())
+            })
+    }
+
+    fn get_nearest_location(&self) -> Option<i64> {
+        self.seeds
+            .iter()
+            .map(|&seed| self.get_seed_location(seed))
+            .min()
     }
+
+    /// Returns `None` if there is no complete `start len` seed range.
+    fn get_nearest_range_location(&self) -> Option<i64> {
+        self.get_seed_range_locations(self.seed_ranges())
+            .iter()
+            .map(|location_range| location_range.start)
+            .min()
+    }
+
+    /// Scans locations upward and returns the first one reached from a seed range, or `None` if
+    /// there is no complete `start len` seed range.
+    ///
+    /// Rather than trying one location at a time, the scan steps through the stretches of
+    /// locations over which the composed map's inverse does not change shape, and looks up the
+    /// seeds landing in each stretch with [`PiecewiseMap::get_inverse_ranges`].
+    pub fn get_nearest_range_location_by_scan(&self) -> Option<i64> {
+        let composed_map = self.compose();
+        let seed_ranges = self.seed_ranges();
+
+        composed_map
+            .get_value_boundaries()
+            .windows(2)
+            .find_map(|window| {
+                composed_map
+                    .get_inverse_ranges(&(window[0]..window[1]))
+                    .iter()
+                    .flat_map(|key_range| {
+                        seed_ranges.iter().filter_map(|seed_range| {
+                            let start = key_range.start.max(seed_range.start);
+                            let end = key_range.end.min(seed_range.end);
+                            (start < end).then(|| composed_map.get_value(start))
+                        })
+                    })
+                    .min()
+            })
+    }
+
+    /// Reference implementation of [`Self::get_nearest_range_location`] that maps every single
+    /// seed.
+    pub fn get_nearest_range_location_brute_force(&self) -> Option<i64> {
+        self.seed_ranges()
+            .into_iter()
+            .flatten()
+            .map(|seed| self.get_seed_location(seed))
+            .min()
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day5)]
+pub fn generate_input(input: &str) -> Result<Almanac, ParseError> {
+    Almanac::parse_str(input)
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day5, part1)]
+pub fn solve_part1(input: &Almanac) -> Option<i64> {
+    input.get_nearest_location()
 }
 
 #[inline]
 #[aoc_runner_derive::aoc(day5, part2)]
-pub fn solve_part2(input: &str) -> i64 {
-    BigAlmanac::parse_str(input).get_nearest_location()
+pub fn solve_part2(input: &Almanac) -> Option<i64> {
+    input.get_nearest_range_location()
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn test_solve_part1_sample() {
-        let input = "seeds: 79 14 55 13
+    const SAMPLE_INPUT: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
@@ -413,44 +1195,398 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(super::solve_part1(input), 35)
+
+    #[test]
+    fn test_solve_part1_sample() {
+        assert_eq!(
+            super::solve_part1(&super::generate_input(SAMPLE_INPUT).unwrap()),
+            Some(35)
+        )
     }
 
     #[test]
     fn test_solve_part2_sample() {
-        let input = "seeds: 79 14 55 13
+        assert_eq!(
+            super::solve_part2(&super::generate_input(SAMPLE_INPUT).unwrap()),
+            Some(46)
+        )
+    }
+
+    #[test]
+    fn test_get_nearest_range_location_sample_matches_brute_force() {
+        let almanac = super::Almanac::parse_str(SAMPLE_INPUT).unwrap();
+        assert_eq!(
+            almanac.get_nearest_range_location(),
+            almanac.get_nearest_range_location_brute_force()
+        )
+    }
+
+    #[test]
+    fn test_get_nearest_range_location_overlapping_matches_brute_force() {
+        let input = "seeds: 0 20 95 10
 
 seed-to-soil map:
-50 98 2
-52 50 48
+50 5 10
+0 14 3
+200 98 5
+
+soil-to-fertilizer map:
+10 0 2
+7 55 4
+
+fertilizer-to-water map:
+
+water-to-light map:
+1000 1 1
+
+light-to-temperature map:
+3 200 2
+300 202 1
+
+temperature-to-humidity map:
+0 300 1
+
+humidity-to-location map:
+5 0 5
+0 5 5";
+        let almanac = super::Almanac::parse_str(input).unwrap();
+        assert_eq!(
+            almanac.get_nearest_range_location(),
+            almanac.get_nearest_range_location_brute_force()
+        );
+        assert_eq!(
+            almanac.get_nearest_range_location_by_scan(),
+            almanac.get_nearest_range_location_brute_force()
+        )
+    }
+
+    #[test]
+    fn test_solve_part1_shuffled_sections() {
+        let input = "seeds: 79 14 55 13
+
+humidity-to-location map:
+60 56 37
+56 93 4
 
 soil-to-fertilizer map:
 0 15 37
 37 52 2
 39 0 15
 
+water-to-light map:
+88 18 7
+18 25 70
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
 fertilizer-to-water map:
 49 53 8
 0 11 42
 42 0 7
 57 7 4
 
-water-to-light map:
-88 18 7
-18 25 70
-
 light-to-temperature map:
 45 77 23
 81 45 19
-68 64 13
+68 64 13";
+        assert_eq!(
+            super::solve_part1(&super::generate_input(input).unwrap()),
+            Some(35)
+        )
+    }
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+    #[test]
+    fn test_solve_part1_fewer_categories() {
+        let input = "seeds: 79 14 55 13
 
-humidity-to-location map:
-60 56 37
-56 93 4";
-        assert_eq!(super::solve_part2(input), 46)
+soil-to-location map:
+0 50 10
+
+seed-to-soil map:
+50 98 2
+52 50 48";
+        assert_eq!(
+            super::solve_part1(&super::generate_input(input).unwrap()),
+            Some(7)
+        )
+    }
+
+    #[test]
+    fn test_compose_sample_matches_seed_locations() {
+        let almanac = super::Almanac::parse_str(SAMPLE_INPUT).unwrap();
+        let composed_map = almanac.compose();
+        for seed in -10..120 {
+            assert_eq!(
+                composed_map.get_value(seed),
+                almanac.get_seed_location(seed)
+            )
+        }
+    }
+
+    #[test]
+    fn test_compose_sample_display_round_trip() {
+        let composed_map = super::Almanac::parse_str(SAMPLE_INPUT).unwrap().compose();
+        let input = format!("seeds: 79 14 55 13\n\nseed-to-location map:\n{composed_map}");
+        assert_eq!(
+            super::Almanac::parse_str(&input).unwrap().compose(),
+            composed_map
+        )
+    }
+
+    #[test]
+    fn test_compose_sample_inverse_values() {
+        let composed_map = super::Almanac::parse_str(SAMPLE_INPUT).unwrap().compose();
+        for location in -10..120 {
+            let seeds = composed_map.get_inverse_values(location);
+            assert!(seeds
+                .iter()
+                .all(|&seed| composed_map.get_value(seed) == location));
+            assert_eq!(
+                seeds.len(),
+                (-200..200)
+                    .filter(|&seed| composed_map.get_value(seed) == location)
+                    .count()
+            )
+        }
+    }
+
+    #[test]
+    fn test_compose_sample_inverse_ranges() {
+        let composed_map = super::Almanac::parse_str(SAMPLE_INPUT).unwrap().compose();
+        for location_range in [-10..0, 0..46, 46..47, 50..120, i64::MIN..i64::MAX] {
+            let key_ranges = composed_map.get_inverse_ranges(&location_range);
+            for seed in -200..200 {
+                assert_eq!(
+                    key_ranges.iter().any(|key_range| key_range.contains(&seed)),
+                    location_range.contains(&composed_map.get_value(seed))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_nearest_range_location_by_scan_sample() {
+        let almanac = super::Almanac::parse_str(SAMPLE_INPUT).unwrap();
+        assert_eq!(almanac.get_nearest_range_location_by_scan(), Some(46))
+    }
+
+    #[test]
+    fn test_parse_str_strict_sample() {
+        assert_eq!(
+            super::Almanac::parse_str_strict(SAMPLE_INPUT),
+            Ok(super::Almanac::parse_str(SAMPLE_INPUT).unwrap())
+        )
+    }
+
+    #[test]
+    fn test_validate_reports_malformed_maps() {
+        use super::{Diagnostic, DiagnosticKind};
+
+        let input = "seeds: 79 x14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+0 60 5
+
+soil-to-fertilizer map:
+0 15 0
+37 52
+39 0 15
+1 9223372036854775807 1
+2 99999999999999999999 1
+
+fertilizer map:
+0 20 5";
+        let map_name = "seed-to-soil";
+        let other_map_name = "soil-to-fertilizer";
+        let diagnostics = vec![
+            Diagnostic::with(
+                "seeds".to_owned(),
+                1,
+                DiagnosticKind::NonNumericField {
+                    field: "x14".to_owned(),
+                },
+            ),
+            Diagnostic::with(
+                map_name.to_owned(),
+                6,
+                DiagnosticKind::Overlap { other_line_no: 5 },
+            ),
+            Diagnostic::with(
+                other_map_name.to_owned(),
+                9,
+                DiagnosticKind::NonPositiveLength { len: 0 },
+            ),
+            Diagnostic::with(
+                other_map_name.to_owned(),
+                10,
+                DiagnosticKind::FieldCount { found: 2 },
+            ),
+            Diagnostic::with(
+                other_map_name.to_owned(),
+                12,
+                DiagnosticKind::RangeEndOutOfRange,
+            ),
+            Diagnostic::with(
+                other_map_name.to_owned(),
+                13,
+                DiagnosticKind::FieldOutOfRange {
+                    field: "99999999999999999999".to_owned(),
+                },
+            ),
+            Diagnostic::with(
+                "fertilizer map:".to_owned(),
+                15,
+                DiagnosticKind::MalformedHeader,
+            ),
+        ];
+        assert_eq!(super::Almanac::validate(input), diagnostics);
+        assert_eq!(super::Almanac::parse_str_strict(input), Err(diagnostics))
+    }
+
+    #[test]
+    fn test_validate_reports_gaps_as_warnings() {
+        let input = "seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 40";
+        let diagnostics = super::Almanac::validate(input);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].is_error());
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning: line 4 (seed-to-soil): source ranges leave a gap at 90..98"
+        );
+        assert!(super::Almanac::parse_str_strict(input).is_ok())
+    }
+
+    #[test]
+    fn test_trace_sample() {
+        let trace = super::Almanac::parse_str(SAMPLE_INPUT).unwrap().trace(79);
+        let values = trace
+            .steps()
+            .iter()
+            .map(|step| step.value())
+            .collect::<Vec<_>>();
+        assert_eq!(values, [79, 81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(
+            trace.to_string(),
+            "category     value  map
+seed         79
+soil         81     seed-to-soil #2: 52 50 48
+fertilizer   81     soil-to-fertilizer identity
+water        81     fertilizer-to-water identity
+light        74     water-to-light #2: 18 25 70
+temperature  78     light-to-temperature #3: 68 64 13
+humidity     78     temperature-to-humidity identity
+location     82     humidity-to-location #1: 60 56 37"
+        )
+    }
+
+    #[test]
+    fn test_trace_range_sample() {
+        let almanac = super::Almanac::parse_str(SAMPLE_INPUT).unwrap();
+        let trace = almanac.trace_range(82..83);
+        assert_eq!(
+            trace.to_string(),
+            "category                range   map
+seed                    82..83
+  soil                  84..85  seed-to-soil #2: 52 50 48
+    fertilizer          84..85  soil-to-fertilizer identity
+      water             84..85  fertilizer-to-water identity
+        light           77..78  water-to-light #2: 18 25 70
+          temperature   45..46  light-to-temperature #1: 45 77 23
+            humidity    46..47  temperature-to-humidity #2: 1 0 69
+              location  46..47  humidity-to-location identity"
+        );
+
+        let trace = almanac.trace_range(79..93);
+        let soil_ranges = trace
+            .children()
+            .iter()
+            .map(|child| child.range().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(soil_ranges, vec![81..95])
+    }
+
+    #[test]
+    fn test_validate_reports_bad_seed_ranges() {
+        use super::{Diagnostic, DiagnosticKind};
+
+        let seeds = |kind| Diagnostic::with("seeds".to_owned(), 1, kind);
+        let diagnostics = super::Almanac::validate("seeds: 79 0 9223372036854775807 5 55");
+        assert_eq!(
+            diagnostics,
+            vec![
+                seeds(DiagnosticKind::NonPositiveLength { len: 0 }),
+                seeds(DiagnosticKind::RangeEndOutOfRange),
+                seeds(DiagnosticKind::Unexpected {
+                    column: 37,
+                    expected: "a seed range length"
+                }),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_parse_str_errors() {
+        let error = super::Almanac::parse_str("seeds: 79 14\n\nseed-to-soil map:\n50 98")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 5, line 4, column 6: expected a `dst src len` range"
+        );
+
+        let error = super::Almanac::parse_str("seeds:\n\nseed-to-soil map:\n50 98 2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 5, line 1, column 7: expected at least one seed number"
+        );
+
+        for (seeds, expected) in [
+            (
+                "seeds: 79 14 55",
+                "day 5, line 1, column 16: expected a seed range length",
+            ),
+            (
+                "seeds: 79 -14",
+                "day 5, line 1, column 11: expected a positive seed range length",
+            ),
+            (
+                "seeds: 9223372036854775807 5",
+                "day 5, line 1, column 28: expected a seed range that fits in an i64",
+            ),
+        ] {
+            let error =
+                super::Almanac::parse_str(&format!("{seeds}\n\nseed-to-soil map:\n50 98 2"))
+                    .unwrap_err()
+                    .to_string();
+            assert_eq!(error, expected);
+        }
+
+        let error = super::Almanac::parse_str("seeds: 79 14\n\nsoil-to-water map:\n50 98 2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "day 5, line 3, column 1: expected a map reachable from `seed`"
+        );
+
+        let diagnostics = super::Almanac::parse_str_strict("seeds: 79 14\n\nsoil-to-water map:");
+        assert_eq!(
+            diagnostics.unwrap_err()[0].to_string(),
+            "error: line 3 (almanac): expected a map reachable from `seed` at column 1"
+        )
     }
 }
@@ -1,25 +1,28 @@
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 6;
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Race {
-    allowed_time: u64,
-    distance_record: u64,
+    allowed_time: u128,
+    distance_record: u128,
 }
 
 impl Race {
     #[inline]
     pub const fn new() -> Self {
-        Self::with(u64::MIN, u64::MIN)
+        Self::with(u128::MIN, u128::MIN)
     }
 
     #[inline]
-    pub const fn with(allowed_time: u64, distance_record: u64) -> Self {
+    pub const fn with(allowed_time: u128, distance_record: u128) -> Self {
         Self {
             allowed_time,
             distance_record,
         }
     }
 
-    #[inline]
-    pub fn get_number_of_ways_to_win(&self) -> u64 {
+    pub fn get_number_of_ways_to_win(&self) -> u128 {
         // hold_time * (allowed_time - hold_time) < distance_record
         // =>  x * (t - x) < d
         // => tx - x^2 < d
@@ -29,68 +32,153 @@ impl Race {
         // a = 1, b = -t, c = d
         // disc = b^2 - 4ac = (-t)^2 - 4(1)(d) = t^2 - 4d
         // x = (-b +- sqrt(disc)) / 2a = (t +- sqrt(disc)) / 2
+        //
+        // The winning hold times lie strictly between both roots, symmetric around t / 2, so
+        // counting them only needs the smallest one.
+
+        let (allowed_time, distance_record) = (self.allowed_time, self.distance_record);
+        if !self.is_win(allowed_time / 2) {
+            return u128::MIN;
+        }
+
+        let min_hold_time = match allowed_time
+            .checked_mul(allowed_time)
+            .and_then(|square| square.checked_sub(distance_record.checked_mul(4)?))
+        {
+            Some(disc) => {
+                // isqrt(disc) <= sqrt(disc) < isqrt(disc) + 1, so the smallest winning hold time
+                // is either this estimate or the one right after it.
+                let min_hold_time = (allowed_time - isqrt(disc)) / 2;
+                min_hold_time + u128::from(!self.is_win(min_hold_time))
+            }
+            // t^2 does not fit in a u128, so search for the smallest winning hold time instead,
+            // as the distance only grows up to t / 2.
+            None => {
+                let (mut low, mut high) = (u128::MIN, allowed_time / 2);
+                while low < high {
+                    let mid = low + (high - low) / 2;
+                    if self.is_win(mid) {
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+
+                low
+            }
+        };
 
-        let disc = self.allowed_time.pow(2) - 4 * self.distance_record;
-        let disc = (disc as f64).sqrt() - 2.0f64;
+        allowed_time - 2 * min_hold_time + 1
+    }
+
+    /// Returns whether holding the button for `hold_time` beats the record, treating a distance
+    /// too large for a u128 as a win.
+    #[inline]
+    fn is_win(&self, hold_time: u128) -> bool {
+        hold_time
+            .checked_mul(self.allowed_time - hold_time)
+            .is_none_or(|distance| distance > self.distance_record)
+    }
+
+    /// Counts the winning hold times one by one.
+    pub fn get_number_of_ways_to_win_brute_force(&self) -> u128 {
+        (u128::MIN..=self.allowed_time)
+            .filter(|&hold_time| self.is_win(hold_time))
+            .count() as u128
+    }
+}
 
-        let x1 = (self.allowed_time as f64 + disc) / 2.0f64;
-        let x2 = (self.allowed_time as f64 - disc) / 2.0f64;
+/// Returns the largest integer whose square does not exceed `n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
 
-        let x1 = x1.ceil() as u64;
-        let x2 = x2.floor() as u64;
+    // Newton's method from an initial guess above the root decreases monotonically to it.
+    let mut x = 1u128 << (n.ilog2() / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            break x;
+        }
 
-        x1 - x2 + 1
+        x = y;
     }
 }
 
+fn get_lines(input: &str) -> Result<(InputLine<'_>, InputLine<'_>), ParseError> {
+    let mut input = input_lines(DAY, input);
+
+    let allowed_times = input
+        .next()
+        .ok_or(ParseError::new(DAY, 1, 1, "a `Time:` line"))?;
+    let distance_records = input
+        .next()
+        .ok_or(ParseError::new(DAY, 2, 1, "a `Distance:` line"))?;
+
+    Ok((allowed_times, distance_records))
+}
+
 #[inline]
-fn filter_numbers(numbers: &str) -> &str {
-    numbers.split(':').last().unwrap().trim()
+fn filter_numbers<'a>(numbers: &InputLine<'a>) -> Result<&'a str, ParseError> {
+    let (_, numbers) = numbers.split_once(numbers.as_str(), ':', "`:`")?;
+    Ok(numbers)
 }
 
 #[inline]
-fn parse_numbers(numbers: &str) -> impl Iterator<Item = u64> + '_ {
-    filter_numbers(numbers)
+fn parse_numbers(numbers: &InputLine) -> Result<Vec<u128>, ParseError> {
+    filter_numbers(numbers)?
         .split_ascii_whitespace()
-        .map(|num| num.parse().unwrap())
+        .map(|num| numbers.parse(num, "a number"))
+        .collect()
 }
 
 #[aoc_runner_derive::aoc(day6, part1)]
-pub fn solve_part1(input: &str) -> u64 {
-    let mut input = input.lines();
+pub fn solve_part1(input: &str) -> Result<u128, ParseError> {
+    let (allowed_times_line, distance_records_line) = get_lines(input)?;
 
-    let allowed_times = input.next().unwrap();
-    let distance_records = input.next().unwrap();
+    let allowed_times = parse_numbers(&allowed_times_line)?;
+    let distance_records = parse_numbers(&distance_records_line)?;
 
-    let allowed_times = parse_numbers(allowed_times);
-    let distance_records = parse_numbers(distance_records);
+    if distance_records.len() != allowed_times.len() {
+        return Err(distance_records_line.error_at_end("a distance record for every race"));
+    }
 
-    allowed_times
+    Ok(allowed_times
+        .into_iter()
         .zip(distance_records)
         .map(|(allowed_time, distance_record)| {
             let race = Race::with(allowed_time, distance_record);
             race.get_number_of_ways_to_win()
         })
-        .product()
+        .product())
 }
 
 #[inline]
-fn parse_number(numbers: &str) -> u64 {
-    filter_numbers(numbers).replace(' ', "").parse().unwrap()
+fn parse_number(numbers: &InputLine) -> Result<u128, ParseError> {
+    let digits = filter_numbers(numbers)?;
+    if let Some(num) = digits
+        .split_ascii_whitespace()
+        .find(|num| !num.bytes().all(|ch| ch.is_ascii_digit()))
+    {
+        return Err(numbers.error(num, "a digit"));
+    }
+
+    digits
+        .replace(' ', "")
+        .parse()
+        .map_err(|_| numbers.error(digits.trim(), "a number that fits in a u128"))
 }
 
 #[aoc_runner_derive::aoc(day6, part2)]
-pub fn solve_part2(input: &str) -> u64 {
-    let mut input = input.lines();
-
-    let allowed_time = input.next().unwrap();
-    let distance_record = input.next().unwrap();
+pub fn solve_part2(input: &str) -> Result<u128, ParseError> {
+    let (allowed_time, distance_record) = get_lines(input)?;
 
-    let allowed_time = parse_number(allowed_time);
-    let distance_record = parse_number(distance_record);
+    let allowed_time = parse_number(&allowed_time)?;
+    let distance_record = parse_number(&distance_record)?;
 
     let race = Race::with(allowed_time, distance_record);
-    race.get_number_of_ways_to_win()
+    Ok(race.get_number_of_ways_to_win())
 }
 
 #[cfg(test)]
@@ -99,13 +187,135 @@ mod tests {
     fn test_solve_part1_sample() {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!(super::solve_part1(input), 288)
+        assert_eq!(super::solve_part1(input), Ok(288))
     }
 
     #[test]
     fn test_solve_part2_sample() {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!(super::solve_part2(input), 71503)
+        assert_eq!(super::solve_part2(input), Ok(71503))
+    }
+
+    #[test]
+    fn test_get_number_of_ways_to_win_no_ways() {
+        assert_eq!(super::Race::with(3, 2).get_number_of_ways_to_win(), 0);
+        assert_eq!(super::Race::with(4, 4).get_number_of_ways_to_win(), 0);
+        assert_eq!(super::Race::with(5, 100).get_number_of_ways_to_win(), 0);
+        assert_eq!(super::Race::with(0, 0).get_number_of_ways_to_win(), 0)
+    }
+
+    #[test]
+    fn test_get_number_of_ways_to_win_large() {
+        let race = super::Race::with(u64::MAX.into(), u64::MIN.into());
+        assert_eq!(race.get_number_of_ways_to_win(), u128::from(u64::MAX) - 1);
+
+        let race = super::Race::with(u64::MAX.into(), u64::MAX.into());
+        assert_eq!(race.get_number_of_ways_to_win(), u128::from(u64::MAX) - 3);
+
+        let race = super::Race::with(u128::MAX, u128::MIN);
+        assert_eq!(race.get_number_of_ways_to_win(), u128::MAX - 1);
+
+        // Only the hold times whose distance does not fit in a u128 beat this record.
+        let race = super::Race::with(u128::MAX, u128::MAX);
+        assert_eq!(race.get_number_of_ways_to_win(), u128::MAX - 3)
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let error = super::solve_part1("Time:      7  15   30\nDistance:  9  40").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 6, line 2, column 17: expected a distance record for every race"
+        );
+
+        let error =
+            super::solve_part2("Time:      7  1x5   30\nDistance:  9  40  200").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 6, line 1, column 15: expected a digit"
+        );
+
+        let error = super::solve_part2("Time:      7  15   30").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 6, line 2, column 1: expected a `Distance:` line"
+        )
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_get_number_of_ways_to_win_matches_brute_force(
+            allowed_time in 0u128..2_000,
+            distance_record in 0u128..1_100_000,
+        ) {
+            let race = super::Race::with(allowed_time, distance_record);
+            proptest::prop_assert_eq!(
+                race.get_number_of_ways_to_win(),
+                race.get_number_of_ways_to_win_brute_force()
+            );
+        }
+
+        #[test]
+        fn test_get_number_of_ways_to_win_perfect_square_boundary(
+            allowed_time in (1u128 << 32)..(1 << 43),
+            hold_time in 1u128..(1 << 20),
+        ) {
+            // Holding for `hold_time` exactly matches the record, so only the hold times strictly
+            // between it and `allowed_time - hold_time` win.
+            let distance_record = hold_time * (allowed_time - hold_time);
+            let race = super::Race::with(allowed_time, distance_record);
+            proptest::prop_assert_eq!(
+                race.get_number_of_ways_to_win(),
+                allowed_time - 2 * hold_time - 1
+            );
+
+            let race = super::Race::with(allowed_time, distance_record - 1);
+            proptest::prop_assert_eq!(
+                race.get_number_of_ways_to_win(),
+                allowed_time - 2 * hold_time + 1
+            );
+        }
+
+        #[test]
+        fn test_get_number_of_ways_to_win_perfect_square_boundary_above_u64(
+            allowed_time in (1u128 << 64)..(1 << 100),
+            hold_time in 1u128..(1 << 20),
+        ) {
+            let distance_record = hold_time * (allowed_time - hold_time);
+            let race = super::Race::with(allowed_time, distance_record);
+            proptest::prop_assert_eq!(
+                race.get_number_of_ways_to_win(),
+                allowed_time - 2 * hold_time - 1
+            );
+
+            let race = super::Race::with(allowed_time, distance_record - 1);
+            proptest::prop_assert_eq!(
+                race.get_number_of_ways_to_win(),
+                allowed_time - 2 * hold_time + 1
+            );
+        }
+
+        #[test]
+        fn test_get_number_of_ways_to_win_min_hold_time(allowed_time: u128, distance_record: u128) {
+            // The count is right exactly when the smallest hold time it implies wins and the one
+            // before it does not.
+            let race = super::Race::with(allowed_time, distance_record);
+            let ways = race.get_number_of_ways_to_win();
+            if ways > 0 {
+                let min_hold_time = (allowed_time - ways).div_ceil(2);
+                proptest::prop_assert!(race.is_win(min_hold_time));
+                proptest::prop_assert!(min_hold_time == 0 || !race.is_win(min_hold_time - 1));
+            } else {
+                proptest::prop_assert!(!race.is_win(allowed_time / 2));
+            }
+        }
+
+        #[test]
+        fn test_isqrt(n: u128) {
+            let root = super::isqrt(n);
+            proptest::prop_assert!(root * root <= n);
+            proptest::prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|square| square > n));
+        }
     }
 }
@@ -1,7 +1,9 @@
-use std::array;
+use crate::error::{input_lines, InputLine, ParseError};
 
-const fn evaluate_card(card: u8, has_joker: bool) -> u8 {
-    match card {
+const DAY: u8 = 7;
+
+const fn evaluate_card(card: u8, has_joker: bool) -> Option<u8> {
+    let card = match card {
         b'2'..=b'9' => card - b'0',
         b'T' => 10,
         b'J' => {
@@ -14,8 +16,10 @@ const fn evaluate_card(card: u8, has_joker: bool) -> u8 {
         b'Q' => 12,
         b'K' => 13,
         b'A' => 14,
-        _ => unreachable!(),
-    }
+        _ => return None,
+    };
+
+    Some(card)
 }
 
 fn count_cards(cards: [u8; 5]) -> (usize, u64) {
@@ -91,22 +95,32 @@ impl Hand {
         }
     }
 
-    pub fn parse_all(input: &str, has_joker: bool) -> Vec<Self> {
-        input
-            .lines()
-            .map(|line| Hand::parse(line, has_joker))
+    pub fn parse_all(input: &str, has_joker: bool) -> Result<Vec<Self>, ParseError> {
+        input_lines(DAY, input)
+            .map(|line| Hand::parse(&line, has_joker))
             .collect()
     }
 
-    pub fn parse(s: &str, has_joker: bool) -> Self {
-        let (cards, bid) = s.trim().split_once(' ').unwrap();
-        let cards = cards.as_bytes();
-        let bid = bid.parse().unwrap();
+    pub fn parse(line: &InputLine, has_joker: bool) -> Result<Self, ParseError> {
+        const EXPECTED_CARD: &str = "a card (`2`-`9`, `T`, `J`, `Q`, `K` or `A`)";
+
+        let (cards_str, bid) = line.split_once(line.as_str().trim(), ' ', "a bid")?;
+        let bid = line.parse(bid.trim(), "a bid")?;
+
+        let cards = cards_str.as_bytes();
+        if cards.len() != 5 {
+            return Err(line.error(cards_str, "5 cards"));
+        }
 
-        let cards = array::from_fn(|i| evaluate_card(cards[i], has_joker));
-        let hand_type = HandType::from_cards(cards);
+        let mut evaluated_cards = [u8::MIN; 5];
+        for (i, (&card, evaluated_card)) in cards.iter().zip(&mut evaluated_cards).enumerate() {
+            *evaluated_card = evaluate_card(card, has_joker).ok_or_else(|| {
+                line.error(cards_str.get(i..).unwrap_or(cards_str), EXPECTED_CARD)
+            })?;
+        }
 
-        Self::with(hand_type, cards, bid)
+        let hand_type = HandType::from_cards(evaluated_cards);
+        Ok(Self::with(hand_type, evaluated_cards, bid))
     }
 }
 
@@ -120,15 +134,15 @@ fn get_winnings(hands: &mut [Hand]) -> u64 {
 }
 
 #[aoc_runner_derive::aoc(day7, part1)]
-pub fn solve_part1(input: &str) -> u64 {
-    let mut hands = Hand::parse_all(input, false);
-    get_winnings(&mut hands)
+pub fn solve_part1(input: &str) -> Result<u64, ParseError> {
+    let mut hands = Hand::parse_all(input, false)?;
+    Ok(get_winnings(&mut hands))
 }
 
 #[aoc_runner_derive::aoc(day7, part2)]
-pub fn solve_part2(input: &str) -> u64 {
-    let mut hands = Hand::parse_all(input, true);
-    get_winnings(&mut hands)
+pub fn solve_part2(input: &str) -> Result<u64, ParseError> {
+    let mut hands = Hand::parse_all(input, true)?;
+    Ok(get_winnings(&mut hands))
 }
 
 #[cfg(test)]
@@ -140,7 +154,7 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
-        assert_eq!(super::solve_part1(input), 6440)
+        assert_eq!(super::solve_part1(input), Ok(6440))
     }
 
     #[test]
@@ -150,6 +164,24 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
-        assert_eq!(super::solve_part2(input), 5905)
+        assert_eq!(super::solve_part2(input), Ok(5905))
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let error = super::solve_part1("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 7, line 2, column 4: expected a card (`2`-`9`, `T`, `J`, `Q`, `K` or `A`)"
+        );
+
+        let error = super::solve_part1("32T3 765").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 7, line 1, column 1: expected 5 cards"
+        );
+
+        let error = super::solve_part2("32T3K").unwrap_err();
+        assert_eq!(error.to_string(), "day 7, line 1, column 6: expected a bid")
     }
 }
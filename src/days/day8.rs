@@ -1,43 +1,400 @@
+use std::{error, fmt};
+
 use ahash::AHashMap;
 
+use crate::error::{input_lines, InputLine, ParseError};
+
+const DAY: u8 = 8;
+
+pub type NodeId = u32;
+
+/// A network whose node labels are interned into dense [`NodeId`]s, so walking it only indexes
+/// into arrays.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Network {
+    instructions: Vec<u8>,
+    labels: Vec<String>,
+    node_ids: AHashMap<String, NodeId>,
+    left_nodes: Vec<NodeId>,
+    right_nodes: Vec<NodeId>,
+}
+
+impl Network {
+    pub fn parse_str(s: &str) -> Result<Self, ParseError> {
+        let mut network = Self::default();
+
+        let mut lines = input_lines(DAY, s);
+        let instructions =
+            lines
+                .next()
+                .ok_or(ParseError::new(DAY, 1, 1, "a line of instructions"))?;
+        network.instructions = Self::parse_instructions(&instructions)?;
+
+        // Where each node was first mentioned, and whether it has been defined.
+        let mut references = Vec::new();
+        let mut is_defined = Vec::new();
+
+        for line in lines.filter(|line| !line.as_str().trim().is_empty()) {
+            let (from_node, to_nodes) = line.split_once(line.as_str(), '=', "`=`")?;
+
+            let from_label = from_node.trim();
+            let to_nodes = to_nodes.trim();
+
+            let to_nodes = to_nodes
+                .strip_prefix('(')
+                .ok_or_else(|| line.error(to_nodes, "`(`"))?;
+            let to_nodes = to_nodes
+                .strip_suffix(')')
+                .ok_or_else(|| line.error_at_end("`)`"))?;
+            let (left_node, right_node) = line.split_once(to_nodes, ',', "`,`")?;
+
+            let from_node = network.intern(from_label, &line, &mut references);
+            let left_node = network.intern(left_node.trim(), &line, &mut references);
+            let right_node = network.intern(right_node.trim(), &line, &mut references);
+
+            is_defined.resize(network.len(), false);
+            if std::mem::replace(&mut is_defined[from_node as usize], true) {
+                return Err(line.error(from_label, "a node defined only once"));
+            }
+
+            network.left_nodes[from_node as usize] = left_node;
+            network.right_nodes[from_node as usize] = right_node;
+        }
+
+        is_defined.resize(network.len(), false);
+        for (is_defined, reference) in is_defined.into_iter().zip(references) {
+            if !is_defined {
+                return Err(reference);
+            }
+        }
+
+        Ok(network)
+    }
+
+    fn parse_instructions(line: &InputLine) -> Result<Vec<u8>, ParseError> {
+        let instructions = line.as_str().trim();
+        if instructions.is_empty() {
+            return Err(line.error_at_end("`L` or `R`"));
+        }
+
+        match instructions.find(|ch| ch != 'L' && ch != 'R') {
+            Some(i) => Err(line.error(&instructions[i..], "`L` or `R`")),
+            None => Ok(instructions.as_bytes().to_vec()),
+        }
+    }
+
+    /// Returns the id of `label`, remembering where it was first mentioned if it is new.
+    fn intern(
+        &mut self,
+        label: &str,
+        line: &InputLine,
+        references: &mut Vec<ParseError>,
+    ) -> NodeId {
+        if let Some(&node) = self.node_ids.get(label) {
+            return node;
+        }
+
+        let node = self.labels.len() as NodeId;
+        self.labels.push(label.to_owned());
+        self.node_ids.insert(label.to_owned(), node);
+        self.left_nodes.push(node);
+        self.right_nodes.push(node);
+        references.push(line.error(label, "the label of a defined node"));
+
+        node
+    }
+
+    #[inline]
+    pub fn instructions(&self) -> &[u8] {
+        &self.instructions
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    #[inline]
+    pub fn get_node(&self, label: &str) -> Option<NodeId> {
+        self.node_ids.get(label).copied()
+    }
+
+    #[inline]
+    pub fn get_label(&self, node: NodeId) -> &str {
+        &self.labels[node as usize]
+    }
+
+    #[inline]
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        NodeId::MIN..(self.labels.len() as NodeId)
+    }
+
+    /// Follows the instruction at `instruction_index` from `node`.
+    #[inline]
+    pub fn get_next_node(&self, node: NodeId, instruction_index: usize) -> NodeId {
+        if self.instructions[instruction_index] == b'L' {
+            self.left_nodes[node as usize]
+        } else {
+            self.right_nodes[node as usize]
+        }
+    }
+
+    /// Counts the steps from `from_node` until `is_end_node` holds, or returns `None` if the walk
+    /// loops forever without reaching such a node.
+    pub fn count_steps(
+        &self,
+        from_node: NodeId,
+        is_end_node: impl Fn(NodeId) -> bool,
+    ) -> Option<u64> {
+        let mut visited = vec![false; self.len() * self.instructions.len()];
+
+        let mut curr_node = from_node;
+        let mut steps = 0;
+
+        loop {
+            if is_end_node(curr_node) {
+                break Some(steps);
+            }
+
+            let instruction_index = (steps % self.instructions.len() as u64) as usize;
+            let state = curr_node as usize * self.instructions.len() + instruction_index;
+            if visited[state] {
+                break None;
+            }
+
+            visited[state] = true;
+            curr_node = self.get_next_node(curr_node, instruction_index);
+            steps += 1;
+        }
+    }
+}
+
+#[inline]
+#[aoc_runner_derive::aoc_generator(day8)]
+pub fn generate_input(input: &str) -> Result<Network, ParseError> {
+    Network::parse_str(input)
+}
+
+/// Returns `None` if there is no `AAA` or `ZZZ` node, or `ZZZ` cannot be reached from `AAA`.
 #[aoc_runner_derive::aoc(day8, part1)]
-pub fn solve_part1(input: &str) -> u64 {
-    let mut lines = input.lines();
-    let mut directions = lines.next().unwrap().bytes().cycle();
+pub fn solve_part1(network: &Network) -> Option<u64> {
+    let start_node = network.get_node("AAA")?;
+    let end_node = network.get_node("ZZZ")?;
+
+    network.count_steps(start_node, |node| node == end_node)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GhostNavigationError {
+    NoStartNodes,
+    NeverFinishes {
+        start_node: String,
+    },
+    NeverAligned,
+    /// The walkers' cycles only line up again after more steps than fit in a u128.
+    StepsOutOfRange,
+}
+
+impl fmt::Display for GhostNavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoStartNodes => write!(f, "no node ends with `A`"),
+            Self::NeverFinishes { start_node } => {
+                write!(
+                    f,
+                    "the walker from {start_node} never reaches a node ending with `Z`"
+                )
+            }
+            Self::NeverAligned => {
+                write!(
+                    f,
+                    "the walkers never sit on nodes ending with `Z` at the same step"
+                )
+            }
+            Self::StepsOutOfRange => write!(f, "the walkers' cycles are too long to line up"),
+        }
+    }
+}
+
+impl error::Error for GhostNavigationError {}
+
+/// The steps `start`, `start + step`, `start + 2 * step`, ..., or only `start` if `step` is zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Progression {
+    start: u128,
+    step: u128,
+}
+
+impl Progression {
+    #[inline]
+    const fn with(start: u128, step: u128) -> Self {
+        Self { start, step }
+    }
 
-    _ = lines.next();
-    let mut adjacency_list = AHashMap::<&str, (&str, &str)>::new();
+    #[inline]
+    const fn contains(&self, n: u128) -> bool {
+        if self.step == 0 {
+            n == self.start
+        } else {
+            n >= self.start && (n - self.start).is_multiple_of(self.step)
+        }
+    }
+
+    /// Returns the steps in both progressions, if there are any, or fails if they do not fit
+    /// in a u128.
+    fn intersect(&self, other: &Self) -> Result<Option<Self>, GhostNavigationError> {
+        use GhostNavigationError::StepsOutOfRange;
+
+        if self.step == 0 {
+            return Ok(other.contains(self.start).then_some(*self));
+        }
+        if other.step == 0 {
+            return Ok(self.contains(other.start).then_some(*other));
+        }
+
+        // Solves n = self.start (mod self.step) and n = other.start (mod other.step).
+        let self_step = i128::try_from(self.step).map_err(|_| StepsOutOfRange)?;
+        let other_step = i128::try_from(other.step).map_err(|_| StepsOutOfRange)?;
+        let (gcd, inverse, _) = extended_gcd(self_step, other_step);
+        let (gcd, inverse) = (
+            gcd.unsigned_abs(),
+            inverse.rem_euclid(other_step).unsigned_abs(),
+        );
+
+        let (self_residue, other_residue) = (self.start % self.step, other.start % other.step);
+        let diff = sub_mod(other_residue, self_residue % other.step, other.step);
+        if !diff.is_multiple_of(gcd) {
+            return Ok(None);
+        }
 
-    for line in lines {
-        let (from_node, to_nodes) = line.split_once('=').unwrap();
+        let reduced_step = other.step / gcd;
+        let k = ((diff / gcd) % reduced_step)
+            .checked_mul(inverse % reduced_step)
+            .ok_or(StepsOutOfRange)?
+            % reduced_step;
 
-        let from_node = from_node.trim();
-        let to_nodes = to_nodes.trim();
+        // Both stay below `step`, as `k` is less than `reduced_step`.
+        let step = self.step.checked_mul(reduced_step).ok_or(StepsOutOfRange)?;
+        let residue = self_residue + self.step * k;
 
-        let to_nodes = &to_nodes[1..(to_nodes.len() - 1)];
-        let (left_node, right_node) = to_nodes.split_once(',').unwrap();
+        let min_start = self.start.max(other.start);
+        let start = min_start
+            .checked_add(sub_mod(residue, min_start % step, step))
+            .ok_or(StepsOutOfRange)?;
+        Ok(Some(Self::with(start, step)))
+    }
+}
 
-        let left_node = left_node.trim();
-        let right_node = right_node.trim();
+/// Returns `a - b` modulo `modulus`, for `a` and `b` below it.
+#[inline]
+const fn sub_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        modulus - (b - a)
+    }
+}
 
-        adjacency_list.insert(from_node, (left_node, right_node));
+/// Returns `(gcd, x, y)` such that `a * x + b * y == gcd`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - (a / b) * y)
     }
+}
+
+/// Walks from `start_node` until a (node, instruction index) state repeats and returns every step
+/// at which the walker sits on a finish node.
+fn get_finish_steps(
+    network: &Network,
+    is_finish_node: &[bool],
+    start_node: NodeId,
+) -> Vec<Progression> {
+    let instructions_len = network.instructions().len();
+    let mut first_seen = vec![u128::MAX; network.len() * instructions_len];
+    let mut finish_steps = Vec::new();
 
-    let mut curr_node = "AAA";
-    let mut steps = 0;
+    let mut curr_node = start_node;
+    let mut steps = 0u128;
+
+    let cycle_start = loop {
+        let instruction_index = (steps % instructions_len as u128) as usize;
+        let state = curr_node as usize * instructions_len + instruction_index;
+        if first_seen[state] != u128::MAX {
+            break first_seen[state];
+        }
 
-    loop {
-        if curr_node == "ZZZ" {
-            break steps;
+        first_seen[state] = steps;
+        if is_finish_node[curr_node as usize] {
+            finish_steps.push(steps);
         }
 
+        curr_node = network.get_next_node(curr_node, instruction_index);
         steps += 1;
-        curr_node = if directions.next().unwrap() == b'L' {
-            adjacency_list[curr_node].0
-        } else {
-            adjacency_list[curr_node].1
-        };
+    };
+
+    let cycle_len = steps - cycle_start;
+    finish_steps
+        .into_iter()
+        .map(|finish_step| {
+            if finish_step < cycle_start {
+                Progression::with(finish_step, 0)
+            } else {
+                Progression::with(finish_step, cycle_len)
+            }
+        })
+        .collect()
+}
+
+#[aoc_runner_derive::aoc(day8, part2)]
+pub fn solve_part2(network: &Network) -> Result<u128, GhostNavigationError> {
+    let is_finish_node = network
+        .nodes()
+        .map(|node| network.get_label(node).ends_with('Z'))
+        .collect::<Vec<_>>();
+
+    let mut start_nodes = network
+        .nodes()
+        .filter(|&node| network.get_label(node).ends_with('A'))
+        .collect::<Vec<_>>();
+    start_nodes.sort_unstable_by_key(|&node| network.get_label(node));
+
+    if start_nodes.is_empty() {
+        return Err(GhostNavigationError::NoStartNodes);
     }
+
+    let mut common_steps = vec![Progression::with(0, 1)];
+    for start_node in start_nodes {
+        let finish_steps = get_finish_steps(network, &is_finish_node, start_node);
+        if finish_steps.is_empty() {
+            let start_node = network.get_label(start_node).to_owned();
+            return Err(GhostNavigationError::NeverFinishes { start_node });
+        }
+
+        common_steps = common_steps
+            .iter()
+            .flat_map(|common| {
+                finish_steps
+                    .iter()
+                    .filter_map(|finish| common.intersect(finish).transpose())
+            })
+            .collect::<Result<_, _>>()?;
+        common_steps.sort_unstable_by_key(|progression| (progression.start, progression.step));
+        common_steps.dedup();
+    }
+
+    common_steps
+        .iter()
+        .map(|progression| progression.start)
+        .min()
+        .ok_or(GhostNavigationError::NeverAligned)
 }
 
 #[cfg(test)]
@@ -53,7 +410,10 @@ DDD = (DDD, DDD)
 EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(super::solve_part1(input), 2)
+        assert_eq!(
+            super::solve_part1(&super::generate_input(input).unwrap()),
+            Some(2)
+        )
     }
 
     #[test]
@@ -63,6 +423,156 @@ ZZZ = (ZZZ, ZZZ)";
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(super::solve_part1(input), 6)
+        assert_eq!(
+            super::solve_part1(&super::generate_input(input).unwrap()),
+            Some(6)
+        )
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert_eq!(
+            super::solve_part2(&super::generate_input(input).unwrap()),
+            Ok(6)
+        )
+    }
+
+    #[test]
+    fn test_solve_part2_offset_cycles() {
+        // 11A reaches 11Z at steps 3, 5, 7, ... and 22A reaches 22Z at steps 2, 5, 8, ..., so
+        // the lcm of the first hits (6) is wrong.
+        let input = "L
+
+11A = (11B, 11B)
+11B = (11C, 11C)
+11C = (11Z, 11Z)
+11Z = (11C, 11C)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22D, 22D)
+22D = (22Z, 22Z)";
+        assert_eq!(
+            super::solve_part2(&super::generate_input(input).unwrap()),
+            Ok(5)
+        )
+    }
+
+    #[test]
+    fn test_solve_part2_never_aligned() {
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22Z, 22Z)";
+        assert_eq!(
+            super::solve_part2(&super::generate_input(input).unwrap()),
+            Err(super::GhostNavigationError::NeverAligned)
+        )
+    }
+
+    #[test]
+    fn test_intersect_large_steps() {
+        use super::{GhostNavigationError, Progression};
+
+        let (a, b) = (Progression::with(5, 1 << 64), Progression::with(1, 3));
+        assert_eq!(
+            a.intersect(&b),
+            Ok(Some(Progression::with((1 << 65) + 5, 3 << 64)))
+        );
+
+        let (a, b) = (
+            Progression::with(1, 1 << 100),
+            Progression::with(0, (1 << 100) - 1),
+        );
+        assert_eq!(a.intersect(&b), Err(GhostNavigationError::StepsOutOfRange));
+
+        let (a, b) = (Progression::with(0, u128::MAX), Progression::with(0, 2));
+        assert_eq!(a.intersect(&b), Err(GhostNavigationError::StepsOutOfRange))
+    }
+
+    #[test]
+    fn test_solve_part2_never_finishes() {
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22A, 22A)";
+        assert_eq!(
+            super::solve_part2(&super::generate_input(input).unwrap()),
+            Err(super::GhostNavigationError::NeverFinishes {
+                start_node: "22A".to_owned()
+            })
+        )
+    }
+
+    #[test]
+    fn test_network_interning() {
+        let network = super::Network::parse_str(
+            "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)",
+        )
+        .unwrap();
+        assert_eq!(network.len(), 3);
+
+        let bbb = network.get_node("BBB").unwrap();
+        assert_eq!(network.get_label(bbb), "BBB");
+        assert_eq!(network.get_label(network.get_next_node(bbb, 0)), "AAA");
+        assert_eq!(network.get_label(network.get_next_node(bbb, 2)), "ZZZ");
+        assert_eq!(network.get_node("CCC"), None)
+    }
+
+    #[test]
+    fn test_generate_input_errors() {
+        let error = super::generate_input("LRX\n\nAAA = (BBB, BBB)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 8, line 1, column 3: expected `L` or `R`"
+        );
+
+        let error = super::generate_input("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA ZZZ)").unwrap_err();
+        assert_eq!(error.to_string(), "day 8, line 4, column 15: expected `,`");
+
+        let error = super::generate_input("LR\n\nAAA (BBB, BBB)").unwrap_err();
+        assert_eq!(error.to_string(), "day 8, line 3, column 15: expected `=`");
+
+        let error = super::generate_input("LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 8, line 3, column 13: expected the label of a defined node"
+        );
+
+        let error = super::generate_input("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 8, line 4, column 1: expected a node defined only once"
+        )
+    }
+
+    #[test]
+    fn test_solve_part1_missing_nodes() {
+        let input = super::generate_input("L\n\nAAA = (AAA, AAA)").unwrap();
+        assert_eq!(super::solve_part1(&input), None);
+
+        let input = super::generate_input("L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert_eq!(super::solve_part1(&input), None)
     }
 }
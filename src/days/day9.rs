@@ -0,0 +1,114 @@
+use crate::error::{input_lines, ParseError};
+
+const DAY: u8 = 9;
+
+/// Returns `sequence` followed by its repeated differences, stopping at the first row that is
+/// all zeros (or empty), or `None` if a difference does not fit in an i64.
+pub fn get_difference_table(sequence: &[i64]) -> Option<Vec<Vec<i64>>> {
+    let mut difference_table = vec![sequence.to_vec()];
+    loop {
+        let last_row = difference_table.last().unwrap();
+        if last_row.iter().all(|&value| value == 0) {
+            break Some(difference_table);
+        }
+
+        let differences = last_row
+            .windows(2)
+            .map(|window| window[1].checked_sub(window[0]))
+            .collect::<Option<_>>()?;
+        difference_table.push(differences);
+    }
+}
+
+/// Predicts the value right after `sequence`, or returns `None` if a step of the prediction
+/// does not fit in an i64.
+pub fn extrapolate_forward(sequence: &[i64]) -> Option<i64> {
+    get_difference_table(sequence)?
+        .iter()
+        .filter_map(|row| row.last())
+        .try_fold(0i64, |next_value, &value| next_value.checked_add(value))
+}
+
+/// Predicts the value right before `sequence`, or returns `None` if a step of the prediction
+/// does not fit in an i64.
+pub fn extrapolate_backward(sequence: &[i64]) -> Option<i64> {
+    get_difference_table(sequence)?
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .try_fold(0i64, |prev_value, &value| value.checked_sub(prev_value))
+}
+
+#[aoc_runner_derive::aoc_generator(day9)]
+pub fn generate_input(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    input_lines(DAY, input)
+        .map(|line| {
+            line.as_str()
+                .split_ascii_whitespace()
+                .map(|num| line.parse(num, "a number"))
+                .collect()
+        })
+        .collect()
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day9, part1)]
+pub fn solve_part1(input: &[Vec<i64>]) -> Option<i64> {
+    input.iter().try_fold(0i64, |sum, history| {
+        sum.checked_add(extrapolate_forward(history)?)
+    })
+}
+
+#[inline]
+#[aoc_runner_derive::aoc(day9, part2)]
+pub fn solve_part2(input: &[Vec<i64>]) -> Option<i64> {
+    input.iter().try_fold(0i64, |sum, history| {
+        sum.checked_add(extrapolate_backward(history)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_solve_part1_sample() {
+        let input = super::generate_input(
+            "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part1(&input), Some(114))
+    }
+
+    #[test]
+    fn test_solve_part2_sample() {
+        let input = super::generate_input(
+            "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45",
+        )
+        .unwrap();
+        assert_eq!(super::solve_part2(&input), Some(2))
+    }
+
+    #[test]
+    fn test_extrapolate_short_sequences() {
+        assert_eq!(super::extrapolate_forward(&[]), Some(0));
+        assert_eq!(super::extrapolate_backward(&[]), Some(0));
+        assert_eq!(super::extrapolate_forward(&[7]), Some(7));
+        assert_eq!(super::extrapolate_backward(&[7]), Some(7));
+        assert_eq!(super::extrapolate_forward(&[1, 4]), Some(7));
+        assert_eq!(super::extrapolate_backward(&[1, 4]), Some(-2))
+    }
+
+    #[test]
+    fn test_extrapolate_out_of_range() {
+        let input = super::generate_input("-9223372036854775808 9223372036854775807").unwrap();
+        assert_eq!(super::get_difference_table(&input[0]), None);
+        assert_eq!(super::solve_part1(&input), None);
+        assert_eq!(super::solve_part2(&input), None);
+
+        assert_eq!(super::extrapolate_forward(&[i64::MAX - 1, i64::MAX]), None);
+        assert_eq!(super::extrapolate_backward(&[i64::MIN, i64::MIN + 1]), None)
+    }
+}
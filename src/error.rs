@@ -0,0 +1,122 @@
+use std::{error, fmt, str::FromStr};
+
+/// An error found while parsing a puzzle input, pointing at the offending position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    day: u8,
+    line_no: usize,
+    column: usize,
+    expected: &'static str,
+}
+
+impl ParseError {
+    #[inline]
+    pub const fn new(day: u8, line_no: usize, column: usize, expected: &'static str) -> Self {
+        Self {
+            day,
+            line_no,
+            column,
+            expected,
+        }
+    }
+
+    #[inline]
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the 1-based line number.
+    #[inline]
+    pub const fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    /// Returns the 1-based column, in bytes.
+    #[inline]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+
+    #[inline]
+    pub const fn expected(&self) -> &'static str {
+        self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}",
+            self.day, self.line_no, self.column, self.expected
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// A line of a puzzle input that knows where it came from, so errors can point into it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputLine<'a> {
+    day: u8,
+    line_no: usize,
+    line: &'a str,
+}
+
+impl<'a> InputLine<'a> {
+    #[inline]
+    pub const fn new(day: u8, line_no: usize, line: &'a str) -> Self {
+        Self { day, line_no, line }
+    }
+
+    #[inline]
+    pub const fn line_no(&self) -> usize {
+        self.line_no
+    }
+
+    #[inline]
+    pub const fn as_str(&self) -> &'a str {
+        self.line
+    }
+
+    /// Returns an error pointing at `at`, which should be a slice of this line. Any other slice
+    /// points just past the end of the line.
+    pub fn error(&self, at: &str, expected: &'static str) -> ParseError {
+        let offset = (at.as_ptr() as usize).wrapping_sub(self.line.as_ptr() as usize);
+        let column = offset.min(self.line.len()) + 1;
+
+        ParseError::new(self.day, self.line_no, column, expected)
+    }
+
+    /// Returns an error pointing just past the end of the line.
+    #[inline]
+    pub fn error_at_end(&self, expected: &'static str) -> ParseError {
+        self.error(&self.line[self.line.len()..], expected)
+    }
+
+    #[inline]
+    pub fn parse<T: FromStr>(&self, at: &str, expected: &'static str) -> Result<T, ParseError> {
+        at.parse().map_err(|_| self.error(at, expected))
+    }
+
+    /// Splits `at` around the first `delimiter`, or fails expecting `expected`.
+    #[inline]
+    pub fn split_once(
+        &self,
+        at: &'a str,
+        delimiter: char,
+        expected: &'static str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        at.split_once(delimiter)
+            .ok_or_else(|| self.error(&at[at.len()..], expected))
+    }
+}
+
+/// Numbers the lines of a puzzle input from 1.
+#[inline]
+pub fn input_lines(day: u8, input: &str) -> impl Iterator<Item = InputLine<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(move |(i, line)| InputLine::new(day, i + 1, line))
+}
@@ -2,5 +2,6 @@ extern crate aoc_runner;
 extern crate aoc_runner_derive;
 
 pub mod days;
+pub mod error;
 
 aoc_runner_derive::aoc_lib! { year = 2023 }